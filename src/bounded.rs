@@ -0,0 +1,43 @@
+use crate::Impartial;
+use serde::{Deserialize, Serialize};
+
+/// a game which ends after at most `remaining` more moves,
+/// used to study truncated variants of existing games
+/// the position is never split into parts since the move limit is shared by all of them
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Bounded<G> {
+    pub game: G,
+    pub remaining: usize,
+}
+
+impl<G> Bounded<G> {
+    pub fn new(game: G, remaining: usize) -> Bounded<G> {
+        Bounded { game, remaining }
+    }
+}
+
+impl<G> Impartial<Bounded<G>> for Bounded<G>
+where
+    G: Impartial<G>,
+{
+    fn get_parts(self) -> Vec<Bounded<G>> {
+        vec![self]
+    }
+    /// a game which ends after at most n moves can not have a nimber above n
+    fn get_max_nimber(&self) -> usize {
+        self.remaining
+    }
+    fn get_unique_moves(&self) -> Vec<Bounded<G>> {
+        if self.remaining == 0 {
+            return vec![];
+        }
+        self.game
+            .get_unique_moves()
+            .into_iter()
+            .map(|game| Bounded::new(game, self.remaining - 1))
+            .collect()
+    }
+    fn label(&self) -> String {
+        format!("{} ({} moves left)", self.game.label(), self.remaining)
+    }
+}
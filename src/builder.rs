@@ -0,0 +1,155 @@
+use crate::entry::{MoveIterators, Parts};
+use crate::spill::{Discard, DiskSpill, Spill, SpillBackend};
+use crate::transient::Transient;
+use crate::{default_poll_interval, CheckpointHook, Evaluator, Impartial, Metrics, MoveOrder, ParallelEvaluation};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// configures an evaluator in one place, every option not set keeps the default of `Evaluator::new`
+pub struct EvaluatorBuilder<G>
+where
+    G: Impartial<G>,
+{
+    capacity: Option<usize>,
+    /// where the evicted nimbers go, they are discarded if no backend is set
+    spill_backend: Option<Arc<Mutex<dyn SpillBackend<G>>>>,
+    poll_interval: usize,
+    nimber_ceiling: Option<usize>,
+    move_order: MoveOrder<G>,
+    checkpoint: Option<(Duration, CheckpointHook<G>)>,
+    parallel_evaluation: Option<ParallelEvaluation<G>>,
+}
+
+impl<G> Default for EvaluatorBuilder<G>
+where
+    G: Impartial<G>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<G> EvaluatorBuilder<G>
+where
+    G: Impartial<G>,
+{
+    pub fn new() -> EvaluatorBuilder<G> {
+        EvaluatorBuilder {
+            capacity: None,
+            spill_backend: None,
+            poll_interval: default_poll_interval(),
+            nimber_ceiling: None,
+            move_order: MoveOrder::default(),
+            checkpoint: None,
+            parallel_evaluation: None,
+        }
+    }
+    /// keeps at most `max_entries` games in memory, like `Evaluator::with_capacity`
+    pub fn capacity(mut self, max_entries: usize) -> Self {
+        self.capacity = Some(max_entries);
+        self
+    }
+    /// the rule out steps between two checks for a cancellation, like `Evaluator::set_cancellation_poll_interval`
+    pub fn cancellation_poll_interval(mut self, steps: usize) -> Self {
+        self.poll_interval = steps;
+        self
+    }
+    /// fails evaluations once a nimber exceeds `max`, like `Evaluator::with_nimber_ceiling`
+    pub fn nimber_ceiling(mut self, max: usize) -> Self {
+        self.nimber_ceiling = Some(max);
+        self
+    }
+    /// the order in which the moves are visited, like `Evaluator::set_move_order`
+    pub fn move_order(mut self, order: MoveOrder<G>) -> Self {
+        self.move_order = order;
+        self
+    }
+    /// calls the hook between slices of `interval`, like `Evaluator::set_checkpoint_hook`
+    pub fn checkpoint_hook(mut self, interval: Duration, hook: CheckpointHook<G>) -> Self {
+        self.checkpoint = Some((interval, hook));
+        self
+    }
+    pub fn build(self) -> Evaluator<G> {
+        Evaluator {
+            data: vec![],
+            index_map: HashMap::new(),
+            misere_outcomes: HashMap::new(),
+            remoteness: HashMap::new(),
+            processing_entries: 0,
+            peak_processing_entries: 0,
+            done_entries: 0,
+            orphans: HashSet::new(),
+            metrics: Metrics::default(),
+            max_nimber: None,
+            spill: self.capacity.map(|max_entries| match self.spill_backend {
+                Some(backend) => Spill::with_backend(backend, max_entries),
+                None => Spill::new(Discard, max_entries),
+            }),
+            cancellation_token: Transient::default(),
+            deadline: None,
+            move_budget: None,
+            max_depth: None,
+            poll_interval: self.poll_interval,
+            steps_since_poll: Transient(0),
+            depth: 0,
+            depth_exceeded: false,
+            nimber_ceiling: self.nimber_ceiling,
+            ceiling_exceeded: false,
+            spill_error: None,
+            bound_violation: false,
+            interrupted: Transient::default(),
+            uncancellable: false,
+            move_order: Transient(self.move_order),
+            checkpoint: Transient(self.checkpoint),
+            on_new_max_nimber: Transient(None),
+            on_done: Transient(None),
+            done_log: Transient::default(),
+            next_generation: Transient::default(),
+            parallel_evaluation: Transient(self.parallel_evaluation),
+            shared: Transient(None),
+            #[cfg(debug_assertions)]
+            move_hashes: Transient::default(),
+        }
+    }
+}
+
+impl<G> EvaluatorBuilder<G>
+where
+    G: Impartial<G> + Serialize,
+{
+    /// keeps at most `limit` games in memory and spills the nimbers of evicted games to the file at `path`,
+    /// like `Evaluator::with_spill`
+    pub fn spill(mut self, path: &Path, limit: usize) -> io::Result<Self> {
+        self.spill_backend = Some(Arc::new(Mutex::new(DiskSpill::create(path)?)));
+        self.capacity = Some(limit);
+        Ok(self)
+    }
+}
+
+impl<G> EvaluatorBuilder<G>
+where
+    G: Impartial<G> + Send + Sync,
+{
+    /// evaluates the moves or parts of queried games on `thread_count` threads, like `Evaluator::with_thread_count`
+    pub fn threads(self, thread_count: usize) -> Self {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(thread_count)
+            .build()
+            .expect("failed to build the thread pool");
+        self.thread_pool(Arc::new(pool))
+    }
+    /// like `threads` but all the parallel work is done on the given thread pool
+    pub fn thread_pool(mut self, pool: Arc<ThreadPool>) -> Self {
+        self.parallel_evaluation = Some(Arc::new(
+            move |evaluator: &mut Evaluator<G>, sums: &[(Parts, usize)], halt_at, iterators: &mut MoveIterators<G>| {
+                evaluator.get_bounded_nimbers_in_pool(sums, halt_at, iterators, &pool)
+            },
+        ));
+        self
+    }
+}
@@ -0,0 +1,41 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// cancels evaluations from anywhere, clones of a token share its state
+/// besides the flag a token remembers why it got cancelled
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    flag: Arc<AtomicBool>,
+    reason: Arc<Mutex<Option<String>>>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+    /// cancels all evaluations using this token
+    pub fn cancel(&self, reason: impl Into<String>) {
+        *self.reason.lock().unwrap() = Some(reason.into());
+        self.flag.store(true, Ordering::Relaxed);
+    }
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+    /// the reason given when the token got cancelled,
+    /// `None` if it is not cancelled or got cancelled through its bare flag
+    pub fn reason(&self) -> Option<String> {
+        if !self.is_cancelled() {
+            return None;
+        }
+        self.reason.lock().unwrap().clone()
+    }
+    /// allows evaluations using this token to run again
+    pub fn reset(&self) {
+        self.flag.store(false, Ordering::Relaxed);
+        *self.reason.lock().unwrap() = None;
+    }
+    /// the bare flag of the token, setting it cancels without a reason
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        self.flag.clone()
+    }
+}
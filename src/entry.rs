@@ -1,25 +1,52 @@
-use crate::Impartial;
+use crate::{Impartial, MoveIter};
 use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, VecDeque};
+use std::mem::size_of;
+use std::sync::Arc;
+
+/// a sum of games given by the indices of its parts,
+/// parts with a nimber known upfront are only kept as the xor of their nimbers
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub(super) struct Parts {
+    pub indices: Vec<usize>,
+    pub modifier: usize,
+}
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub(super) struct Entry<G>
 where
     G: Impartial<G>,
 {
-    game: G,
+    /// is `None` once the entry got evicted, only the nimber is kept in that case
+    /// the game is shared with the key of the entry in the index map
+    game: Option<Arc<G>>,
     possible_nimbers: Vec<usize>,
-    unprocessed_move_indices: Option<Vec<Vec<usize>>>,
+    /// the candidates a move reached, in the order the moves reached them
+    ruled_out: Vec<usize>,
+    /// the moves are taken from the front in the order they were generated in,
+    /// moves which could not be evaluated completely are queued again at the back,
+    /// so every move is examined before any move gets examined a second time
+    unprocessed_move_indices: Option<VecDeque<Parts>>,
+    /// the number of moves taken so far from the iterator of a game generating its moves lazily,
+    /// `None` for games generating their moves at once and after the iterator ran out
+    lazy_moves_taken: Option<usize>,
 }
 
 impl<G> Entry<G>
 where
     G: Impartial<G>
 {
-    pub fn new(game: G) -> Entry<G> {
+    pub fn new(game: Arc<G>) -> Entry<G> {
+        let possible_nimbers = game.get_possible_nimbers();
+        //ruling out nimbers relies on binary searches over the candidates
+        debug_assert!(!possible_nimbers.is_empty(), "a game needs at least one candidate nimber");
+        debug_assert!(possible_nimbers.windows(2).all(|pair| pair[0] < pair[1]), "the candidate nimbers have to be increasing");
         Entry {
-            possible_nimbers : game.get_possible_nimbers(),
-            game: game,
+            possible_nimbers,
+            game: Some(game),
+            ruled_out: vec![],
             unprocessed_move_indices: None,
+            lazy_moves_taken: None,
         }
     }
     pub fn get_nimber(&self) -> Option<usize>{
@@ -31,30 +58,132 @@ where
         }
     }
     pub fn remove_nimber(&mut self, nimber: usize){
-        match self.possible_nimbers.binary_search(&nimber) {
-            Ok(i) => _ = self.possible_nimbers.remove(i),
-            Err(_) => (),
+        if let Ok(i) = self.possible_nimbers.binary_search(&nimber) {
+            self.possible_nimbers.remove(i);
+        }
+    }
+    /// removes a candidate because a move reached it
+    pub fn rule_out(&mut self, nimber: usize) {
+        if let Ok(i) = self.possible_nimbers.binary_search(&nimber) {
+            self.possible_nimbers.remove(i);
+            self.ruled_out.push(nimber);
         }
     }
+    pub fn get_ruled_out(&self) -> &[usize] {
+        &self.ruled_out
+    }
     pub fn set_nimber(&mut self, nimber: usize){
         self.possible_nimbers = vec![nimber];
     }
+    /// rules out every nimber above `max`
+    pub fn limit_nimbers(&mut self, max: usize) {
+        self.possible_nimbers.retain(|nimber| *nimber <= max);
+    }
+    pub fn get_possible_nimbers(&self) -> &[usize] {
+        &self.possible_nimbers
+    }
     pub fn get_smallest_possible_nimber(&self) -> usize{
         self.possible_nimbers[0]
     }
-    pub fn get_next_unprocessed_move_index(&mut self) -> Option<Vec<usize>> {
-        self.unprocessed_move_indices.as_mut().expect("the move indices should already be generated").pop()
+    /// the smallest and the largest nimber this entry could still have
+    pub fn get_nimber_bounds(&self) -> (usize, usize) {
+        (self.possible_nimbers[0], *self.possible_nimbers.last().unwrap())
+    }
+    pub fn get_next_unprocessed_move_index(&mut self) -> Option<Parts> {
+        self.unprocessed_move_indices.as_mut().expect("the move indices should already be generated").pop_front()
+    }
+    pub fn add_unprocessed_move_indices(&mut self, new_unprocessed_move_indices: Vec<Parts>){
+        self.unprocessed_move_indices.as_mut().expect("the move indices should already be generated").extend(new_unprocessed_move_indices);
     }
-    pub fn add_unprocessed_move_indices(&mut self, mut new_unprocessed_move_indices: Vec<Vec<usize>>){
-        self.unprocessed_move_indices.as_mut().expect("the move indices should already be generated").append(&mut new_unprocessed_move_indices);
+    pub fn get_unprocessed_move_indices(&self) -> impl Iterator<Item = &Parts> {
+        self.unprocessed_move_indices.iter().flatten()
+    }
+    #[cfg(feature = "tracing")]
+    pub fn get_unprocessed_move_count(&self) -> usize {
+        self.unprocessed_move_indices.as_ref().map_or(0, |moves| moves.len())
     }
     pub fn are_move_indices_generated(&self) -> bool{
-        return self.unprocessed_move_indices.is_some();
+        self.unprocessed_move_indices.is_some()
+    }
+    pub fn set_child_indices(&mut self, unprocessed_move_indices: Vec<Parts>) {
+        self.unprocessed_move_indices = Some(unprocessed_move_indices.into());
+    }
+    /// takes over the candidates another evaluator left for the same game, see `SharedCache`
+    pub fn restore_nimbers(&mut self, possible_nimbers: Vec<usize>, ruled_out: Vec<usize>) {
+        self.possible_nimbers = possible_nimbers;
+        self.ruled_out = ruled_out;
     }
-    pub fn set_child_indices(&mut self, unprocessed_move_indices: Vec<Vec<usize>>) {
-        self.unprocessed_move_indices = Some(unprocessed_move_indices);
+    /// takes over the moves another evaluator left for the same game, see `SharedCache`
+    pub fn restore_moves(&mut self, unprocessed_move_indices: Vec<Parts>, lazy_moves_taken: Option<usize>) {
+        self.unprocessed_move_indices = Some(unprocessed_move_indices.into());
+        self.lazy_moves_taken = lazy_moves_taken;
+    }
+    /// marks the moves as generated while they are actually taken from an iterator one at a time
+    pub fn start_lazy_moves(&mut self) {
+        self.unprocessed_move_indices = Some(VecDeque::new());
+        self.lazy_moves_taken = Some(0);
+    }
+    pub fn get_lazy_moves_taken(&self) -> Option<usize> {
+        self.lazy_moves_taken
+    }
+    pub fn take_lazy_move(&mut self) {
+        *self.lazy_moves_taken.as_mut().expect("the iterator of the moves ran out already") += 1;
+    }
+    pub fn finish_lazy_moves(&mut self) {
+        self.lazy_moves_taken = None;
+    }
+    pub fn get_game(&self) -> &G {
+        self.get_shared_game()
+    }
+    pub fn get_shared_game(&self) -> &Arc<G> {
+        self.game.as_ref().expect("evicted entries are always done")
     }
     pub fn get_unique_moves(&self) -> Vec<G> {
-        self.game.get_unique_moves()
+        self.get_game().get_unique_moves()
+    }
+    pub fn is_evicted(&self) -> bool {
+        self.game.is_none()
+    }
+    /// the bytes this entry allocates on the heap, the game is only counted while the entry owns it
+    pub fn heap_size(&self) -> usize {
+        let game = self.game.as_ref().map_or(0, |game| {
+            //the game lives in an arc next to its two reference counts
+            2 * size_of::<usize>() + size_of::<G>() + game.heap_size()
+        });
+        let moves = self.unprocessed_move_indices.as_ref().map_or(0, |moves| {
+            moves.capacity() * size_of::<Parts>()
+                + moves.iter().map(|parts| parts.indices.capacity() * size_of::<usize>()).sum::<usize>()
+        });
+        game + (self.possible_nimbers.capacity() + self.ruled_out.capacity()) * size_of::<usize>() + moves
+    }
+    /// moves the parts of the unprocessed moves to their new indices, see `Evaluator::reclaim_evicted_entries`
+    /// a part without a new index got evicted, so its nimber joins the modifier of the move instead
+    pub fn remap_moves(&mut self, new_indices: &[Result<usize, usize>]) {
+        for parts in self.unprocessed_move_indices.iter_mut().flatten() {
+            let mut modifier = parts.modifier;
+            parts.indices.retain_mut(|index| match new_indices[*index] {
+                Ok(new_index) => {
+                    *index = new_index;
+                    true
+                }
+                Err(nimber) => {
+                    modifier ^= nimber;
+                    false
+                }
+            });
+            parts.modifier = modifier;
+        }
+    }
+    /// drops everything but the nimber and hands out the game
+    pub fn evict(&mut self) -> Arc<G> {
+        self.ruled_out = vec![];
+        self.unprocessed_move_indices = None;
+        self.lazy_moves_taken = None;
+        self.game.take().expect("an entry can only be evicted once")
     }
 }
+
+/// the live iterators of the entries generating their moves lazily, indexed like the entries
+/// they belong to the query which created them and are dropped once it returns, even if it panics,
+/// so the iterators needn't be `Send` and are recreated from the number of moves taken by the entries
+pub(super) type MoveIterators<G> = HashMap<usize, MoveIter<G>>;
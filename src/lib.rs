@@ -1,9 +1,47 @@
+mod bounded;
+mod builder;
+mod cancellation;
 mod entry;
+pub mod nim;
+pub mod octal;
+mod period;
+mod persistence;
+mod shared;
+mod spill;
+mod stats;
+pub mod subtraction;
+pub mod sum;
+mod table;
 mod tests;
-use entry::Entry;
+mod transient;
+pub mod wythoff;
+use bitvec::prelude::*;
+use entry::{Entry, MoveIterators, Parts};
+use rayon::prelude::*;
+use rayon::ThreadPool;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::hash::Hash;
+use shared::{Claimed, Progress, SharedCache, Worker};
+use spill::Spill;
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::mem::size_of;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use transient::Transient;
+pub use bounded::Bounded;
+pub use builder::EvaluatorBuilder;
+pub use cancellation::CancellationToken;
+pub use period::find_period;
+pub use stats::{CacheStats, Metrics};
+pub use persistence::PersistenceError;
+pub use table::NimberTable;
 
 /// providing the interface to evaluate an impartial game with the Evaluator
 pub trait Impartial<G>: Sized + Clone + Hash + Eq
@@ -11,157 +49,2016 @@ where
     G: Impartial<G>,
 {
     fn get_parts(self) -> Vec<G>;
+    /// an upper bound of the nimber, every nimber up to it is a candidate kept in memory until it is ruled out
+    /// keep it tight, a bound near `usize::MAX` is only usable together with `move_count`
     fn get_max_nimber(&self) -> usize;
+    /// the candidate nimbers in increasing order, by default every nimber up to the bound
+    /// the nimber never exceeds the number of moves, so a known move count caps the candidates as well
     fn get_possible_nimbers(&self) -> Vec<usize> {
-        (0..=self.get_max_nimber()).collect()
+        let max = self.get_max_nimber().min(self.move_count().unwrap_or(usize::MAX));
+        (0..=max).collect()
     }
+    /// the positions reachable in a single move, without duplicates
+    /// loopy games are not supported, no position may be reachable from itself,
+    /// only a move to the very same position or to a sum with the position as one of its parts is detected and ignored
     fn get_unique_moves(&self) -> Vec<G>;
+    /// a short human readable name of the position, used wherever positions are displayed
+    /// defaults to a short hash, override it for readable output
+    fn label(&self) -> String {
+        format!("#{:08x}", hash_of(self) as u32)
+    }
+    /// returns the size of the nim heap this position is equivalent to, if it is one
+    /// such positions are never recursed into and need no cache entry
+    fn as_nim_heap(&self) -> Option<usize> {
+        None
+    }
+    /// splits a part which is secretly a sum of independent components
+    /// the nimbers of the components are xored instead of evaluating the part as a whole
+    /// must return `None` for positions which can not be split any further
+    fn decompose(&self) -> Option<Vec<G>> {
+        None
+    }
+    /// whether the position has no moves, override it if this is cheaper to tell than generating the moves
+    /// terminal positions get the nimber 0 without generating their moves,
+    /// the default never claims a position to be terminal, those are found once their moves are generated
+    fn is_terminal(&self) -> bool {
+        false
+    }
+    /// the representative of all positions equal to this one, e.g. with sorted heaps or the smaller of two mirror images
+    /// the cache only stores canonical positions, so equal positions share a single entry
+    /// takes the position by value so the default doesn't need to clone it
+    fn canonical(self) -> Self {
+        self
+    }
+    /// the number of unique moves, or at least an upper bound of it, if it is known without generating the moves
+    /// the nimber can't exceed the number of moves, so this tightens the bound of positions before they are expanded
+    fn move_count(&self) -> Option<usize> {
+        None
+    }
+    /// generates the moves one at a time instead of `get_unique_moves`, override it for positions with lots of moves
+    /// a move is only generated once all moves before it failed to confirm the candidate nimber,
+    /// the moves are visited in the order of the iterator and their number doesn't bound the nimber
+    /// the iterator has to yield the same moves in the same order for equal positions,
+    /// `get_unique_moves` is still used wherever all moves are needed at once, e.g. for misère play
+    fn unique_moves_iter(&self) -> Option<MoveIter<G>> {
+        None
+    }
+    /// the bytes the position allocates on the heap, e.g. for a board stored in a `Vec`
+    /// only used to estimate the memory usage of the evaluator, so it defaults to 0
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+/// the outcome class of a position under normal play
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Outcome {
+    /// the previous player wins, the nimber is 0
+    P,
+    /// the next player wins, the nimber is not 0
+    N,
+}
+
+/// the nimber of a game disagrees with the nimber of the sum it was decomposed into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecompositionMismatch {
+    pub whole: usize,
+    pub by_parts: usize,
+}
+
+/// a move of a move graph leads to a position which is not in the graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingPosition<G>(pub G);
+
+/// a justification of the nimber of a game by the mex rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NimberProof<G> {
+    pub nimber: usize,
+    /// `witnesses[k]` is a move to a position with nimber `k`, for every `k` below the nimber
+    pub witnesses: Vec<G>,
+    /// every move together with its nimber, none of them has the nimber of the game
+    pub move_nimbers: Vec<(G, usize)>,
+}
+
+/// the candidate nimbers of a cached position which its moves ruled out so far, see `Evaluator::ruled_out_nimbers`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuledOutNimbers {
+    /// the candidates in the order the moves reached them
+    pub ruled_out: Vec<usize>,
+    /// the nimber once the position is done
+    pub nimber: Option<usize>,
+}
+
+/// the reasons an evaluation can end without a nimber
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EvalError {
+    /// the evaluation got stopped or ran past its deadline, the progress is kept
+    /// `reason` is the reason given to the cancellation token, if any
+    Cancelled { reason: Option<String> },
+    /// the nimber is certainly above the bound
+    BoundExceeded,
+    /// the nimber could not be found without expanding positions deeper than the maximal depth
+    DepthExceeded,
+    /// some position got a nimber above the ceiling of the evaluator, which hints at a bug in the moves
+    CeilingExceeded,
+    /// reading or writing the spill failed, the evaluator stopped using it and discards the nimbers it evicts from then on
+    SpillFailed { kind: io::ErrorKind, message: String },
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::Cancelled { reason: None } => write!(f, "the evaluation got cancelled"),
+            EvalError::Cancelled { reason: Some(reason) } => write!(f, "the evaluation got cancelled: {}", reason),
+            EvalError::BoundExceeded => write!(f, "the nimber is above the bound"),
+            EvalError::DepthExceeded => write!(f, "the evaluation exceeded the maximal depth"),
+            EvalError::CeilingExceeded => write!(f, "a nimber exceeded the ceiling of the evaluator"),
+            EvalError::SpillFailed { message, .. } => write!(f, "the spill failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// the number of move evaluations between two polls of `Evaluator::get_nimber_cooperative`
+const COOPERATIVE_SLICE: usize = 1000;
+
+/// the moves of a position which generates them lazily, see `Impartial::unique_moves_iter`
+/// the iterators only live while a query runs, so they needn't be `Send` even for games evaluated in parallel
+pub type MoveIter<G> = Box<dyn Iterator<Item = G>>;
+
+/// a callback receiving a game together with its nimber
+pub type NimberCallback<G> = Arc<dyn Fn(&G, usize) + Send + Sync>;
+
+/// a hook receiving the evaluator at a safe point of a long evaluation
+pub type CheckpointHook<G> = Arc<dyn Fn(&Evaluator<G>) + Send + Sync>;
+
+/// evaluates sums of entries on multiple threads, each up to its bound, and merges the results into the evaluator
+/// the threads stop once a sum reaches the nimber to halt at, the nimbers of sums which exceeded their bound
+/// or which were stopped are `None`
+type ParallelEvaluation<G> =
+    Arc<dyn Fn(&mut Evaluator<G>, &[(Parts, usize)], Option<usize>, &mut MoveIterators<G>) -> Vec<Option<usize>> + Send + Sync>;
+
+/// the result of an evaluation which might have been cut short
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NimberResult {
+    Exact(usize),
+    /// the nimber lies somewhere between `lower` and `upper`, both inclusive
+    Bounds { lower: usize, upper: usize },
+}
+
+/// a key for ordering moves, moves with smaller keys are visited first
+pub type MoveKey<G> = Arc<dyn Fn(&G) -> i64 + Send + Sync>;
+
+/// the order in which the moves of a position are visited while ruling out nimbers
+/// a good order rules out the candidate nimbers with fewer move evaluations
+#[derive(Clone, Default)]
+pub enum MoveOrder<G> {
+    /// the moves with the biggest possible nimber are visited first
+    #[default]
+    LargestMaxNimberFirst,
+    /// the moves with the smallest possible nimber are visited first
+    SmallestMaxNimberFirst,
+    /// the moves are visited in the order `get_unique_moves` returns them
+    Generated,
+    Custom(MoveKey<G>),
+}
+
+impl<G> fmt::Debug for MoveOrder<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveOrder::LargestMaxNimberFirst => write!(f, "LargestMaxNimberFirst"),
+            MoveOrder::SmallestMaxNimberFirst => write!(f, "SmallestMaxNimberFirst"),
+            MoveOrder::Generated => write!(f, "Generated"),
+            MoveOrder::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
 }
 
 /// Evaluates an impartial game
 /// The generic arguments specify
 /// a generalized version and a smaller part of a generalized impartial game
+/// Cloning copies the cache, only the games themselves and the cancellation token are shared,
+/// see `fork_cache` for a copy which is independent of the original evaluator
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "G: Serialize", deserialize = "G: Deserialize<'de>"))]
 pub struct Evaluator<G>
 where
     G: Impartial<G>,
 {
     data: Vec<Entry<G>>,
-    index_map: HashMap<G, usize>,
+    /// the keys share the games with the entries, so every game is stored only once
+    /// serializing would duplicate the games, so the map is rebuilt from the entries after loading instead
+    #[serde(skip)]
+    index_map: HashMap<Arc<G>, usize>,
+    /// the outcomes of whole games under misère play
+    misere_outcomes: HashMap<G, Outcome>,
+    /// the remoteness of whole games
+    remoteness: HashMap<G, usize>,
+    /// the number of entries whose moves are generated but whose nimber is not yet known
+    processing_entries: usize,
+    peak_processing_entries: usize,
+    /// the number of entries whose nimber is known
+    done_entries: usize,
+    /// the entries of invalidated games, they are only kept for the entries which referred to them before
+    orphans: HashSet<usize>,
+    metrics: Metrics,
+    /// the highest nimber of any entry so far
+    max_nimber: Option<usize>,
+    #[serde(skip)]
+    spill: Option<Spill<G>>,
+    /// shared with everyone who wants to cancel the evaluations
+    #[serde(skip)]
+    cancellation_token: Transient<CancellationToken>,
+    /// evaluations running past the deadline are cancelled
+    #[serde(skip)]
+    deadline: Option<Instant>,
+    /// evaluations running past this number of move evaluations are cancelled
+    #[serde(skip)]
+    move_budget: Option<usize>,
+    /// nested positions only check for cancellations after this many rule out steps
+    #[serde(skip, default = "default_poll_interval")]
+    poll_interval: usize,
+    /// the rule out steps since the cancellation was checked the last time
+    #[serde(skip)]
+    steps_since_poll: Transient<usize>,
+    /// positions nested deeper than this are not expanded
+    #[serde(skip)]
+    max_depth: Option<usize>,
+    /// the number of positions currently being expanded above the current one
+    #[serde(skip)]
+    depth: usize,
+    /// set when an evaluation got cut off by the maximal depth, this cancels the whole evaluation
+    #[serde(skip)]
+    depth_exceeded: bool,
+    /// no position is expected to have a nimber above this
+    #[serde(skip)]
+    nimber_ceiling: Option<usize>,
+    /// set once a nimber exceeded the ceiling, this cancels all evaluations until the cache is cleared
+    #[serde(skip)]
+    ceiling_exceeded: bool,
+    /// set once a position got a nimber above its max nimber, see `had_bound_violation`
+    #[serde(skip)]
+    bound_violation: bool,
+    /// the first failure of the spill which was not reported yet, this cancels the evaluation that ran into it
+    #[serde(skip)]
+    spill_error: Option<EvalError>,
+    /// set when an evaluation returned early because it got cancelled
+    #[serde(skip)]
+    interrupted: Transient<bool>,
+    /// set while an evaluation of the infallible api runs, which ignores cancellations, move budgets and the ceiling
+    #[serde(skip)]
+    uncancellable: bool,
+    #[serde(skip)]
+    move_order: Transient<MoveOrder<G>>,
+    #[serde(skip)]
+    checkpoint: Transient<Option<(Duration, CheckpointHook<G>)>>,
+    #[serde(skip)]
+    on_new_max_nimber: Transient<Option<NimberCallback<G>>>,
+    #[serde(skip)]
+    on_done: Transient<Option<NimberCallback<G>>>,
+    /// the generations and indices of the entries in the order they got done, see `nimbers_since`
+    /// evicted entries are dropped from the log once their slots are reclaimed
+    #[serde(skip)]
+    done_log: Transient<Vec<(u64, usize)>>,
+    /// the generation of the next entry which gets done, it keeps counting when the cache is cleared
+    #[serde(skip)]
+    next_generation: Transient<u64>,
+    #[serde(skip)]
+    parallel_evaluation: Transient<Option<ParallelEvaluation<G>>>,
+    /// the cache this evaluator shares with others evaluating games side by side, see `SharedCache`
+    #[serde(skip)]
+    shared: Transient<Option<Worker<G>>>,
+    /// maps every game whose moves got generated to the hash of its moves
+    #[cfg(debug_assertions)]
+    #[serde(skip)]
+    move_hashes: Transient<HashMap<Arc<G>, u64>>,
 }
 
+impl<G> Default for Evaluator<G>
+where
+    G: Impartial<G>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//the core evaluation needs no Send or Sync, so games sharing structure through an Rc can be evaluated on a single thread
 impl<G> Evaluator<G>
 where
     G: Impartial<G>,
 {
     pub fn new() -> Evaluator<G> {
-        Evaluator {
-            data: vec![],
-            index_map: HashMap::new(),
+        EvaluatorBuilder::new().build()
+    }
+    /// configures an evaluator with several options at once
+    pub fn builder() -> EvaluatorBuilder<G> {
+        EvaluatorBuilder::new()
+    }
+    /// creates an evaluator that keeps at most `max_entries` games in memory,
+    /// the least recently used done entries are forgotten and recalculated when they are needed again
+    /// entries which are still being processed are never evicted, and the slots of evicted entries are reclaimed by the next query
+    /// limits far below the number of games a single evaluation needs make recalculations cascade
+    pub fn with_capacity(max_entries: usize) -> Evaluator<G> {
+        EvaluatorBuilder::new().capacity(max_entries).build()
+    }
+    /// creates an evaluator which fails with `EvalError::CeilingExceeded` once any position gets a nimber above `max`,
+    /// a sanity check for games whose nimbers are known to be small
+    /// the nimbers are still cached, so all further evaluations fail until the cache is cleared
+    pub fn with_nimber_ceiling(max: usize) -> Evaluator<G> {
+        EvaluatorBuilder::new().nimber_ceiling(max).build()
+    }
+    /// returns the flag which cancels all running evaluations of this evaluator and its clones once it is set
+    /// cancelled evaluations keep their progress, the next evaluation continues where they stopped
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancellation_token.0.flag()
+    }
+    /// returns the token which cancels all running evaluations of this evaluator and its clones
+    /// unlike the bare flag it remembers the reason of the cancellation
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation_token.0.clone()
+    }
+    /// cancels all running evaluations
+    pub fn stop(&self) {
+        self.cancellation_token.0.cancel("stopped");
+    }
+    /// allows evaluations to run again after they got stopped
+    pub fn resume(&self) {
+        self.cancellation_token.0.reset();
+    }
+    /// sets how many rule out steps nested positions take between two checks for a cancellation, 1024 by default
+    /// fewer steps make cancellations, deadlines and move budgets take effect sooner at the cost of reading the clock more often
+    pub fn set_cancellation_poll_interval(&mut self, steps: usize) {
+        self.poll_interval = steps;
+    }
+    /// the error of an evaluation which got cancelled
+    fn cancelled(&mut self) -> EvalError {
+        if let Some(error) = self.spill_error.take() {
+            return error;
+        }
+        if self.ceiling_exceeded {
+            return EvalError::CeilingExceeded;
+        }
+        EvalError::Cancelled {
+            reason: self.cancellation_token.0.reason(),
+        }
+    }
+    fn is_cancelled(&self) -> bool {
+        //deadlines only slice evaluations into checkpoints while the evaluation is uncancellable
+        self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            || self.depth_exceeded
+            || self.shared.0.as_ref().is_some_and(Worker::is_halted)
+            || !self.uncancellable
+                && (self.cancellation_token.0.is_cancelled()
+                    || self.move_budget.is_some_and(|budget| self.metrics.move_evaluations >= budget)
+                    || self.ceiling_exceeded
+                    || self.spill_error.is_some())
+    }
+    /// runs an evaluation of the infallible api, which can't be cancelled
+    fn uncancellable<T>(&mut self, evaluate: impl FnOnce(&mut Self) -> T) -> T {
+        let uncancellable = std::mem::replace(&mut self.uncancellable, true);
+        let result = evaluate(self);
+        self.uncancellable = uncancellable;
+        //the infallible api carries on without the spill, so its failures are not reported later on
+        if !uncancellable {
+            self.spill_error = None;
         }
+        result
     }
     /// calculates the nimber of an impartial game
+    /// the evaluation always runs to the end, only the `try_` functions can be cancelled
     pub fn get_nimber(&mut self, g: G) -> usize {
-        return self.get_bounded_nimber(g, usize::max_value()).unwrap();
+        self.get_bounded_nimber(g, usize::MAX).unwrap()
     }
     /// calculates the nimber of an impartial game but stoppes if the evaluator
     /// is certain that the nimber of the game is above the bound
     pub fn get_bounded_nimber(&mut self, g: G, bound: usize) -> Option<usize> {
-        let parts_indices = self.get_part_indices(g);
-        return self.get_bounded_nimber_by_parts(&parts_indices, bound);
+        self.uncancellable(|evaluator| evaluator.try_get_bounded_nimber(g, bound)).ok()
+    }
+    /// calculates the nimber of an impartial game
+    pub fn try_get_nimber(&mut self, g: G) -> Result<usize, EvalError> {
+        self.try_get_bounded_nimber(g, usize::MAX)
+    }
+    /// calculates the nimber of an impartial game but stoppes if the evaluator
+    /// is certain that the nimber of the game is above the bound
+    pub fn try_get_bounded_nimber(&mut self, g: G, bound: usize) -> Result<usize, EvalError> {
+        let parts = self.get_query_parts(g);
+        self.interrupted.0 = false;
+        let nimber = match self.checkpoint.0.clone() {
+            Some((interval, hook)) => self.get_bounded_nimber_with_checkpoints(&parts, bound, interval, &hook),
+            None => self.get_bounded_nimber_by_parts(&parts, bound, &mut MoveIterators::default()),
+        };
+        match nimber {
+            Some(_) if self.ceiling_exceeded && !self.uncancellable => Err(EvalError::CeilingExceeded),
+            Some(_) if self.spill_error.is_some() && !self.uncancellable => Err(self.cancelled()),
+            Some(nimber) => Ok(nimber),
+            None if self.interrupted.0 => Err(self.cancelled()),
+            None => Err(EvalError::BoundExceeded),
+        }
+    }
+    /// calculates the nimber of an impartial game, expanding at most `max_depth` nested positions
+    /// nimbers which are already known are used at any depth, so deepening step by step reuses all progress
+    /// returns `None` if the depth was exceeded or the evaluation got cancelled
+    pub fn get_nimber_with_depth(&mut self, g: G, max_depth: usize) -> Option<usize> {
+        self.try_get_nimber_with_depth(g, max_depth).ok()
+    }
+    /// calculates the nimber of an impartial game, expanding at most `max_depth` nested positions
+    pub fn try_get_nimber_with_depth(&mut self, g: G, max_depth: usize) -> Result<usize, EvalError> {
+        let parts = self.get_query_parts(g);
+        self.max_depth = Some(max_depth);
+        self.interrupted.0 = false;
+        let nimber = self.get_bounded_nimber_by_parts(&parts, usize::MAX, &mut MoveIterators::default());
+        self.max_depth = None;
+        let depth_exceeded = std::mem::take(&mut self.depth_exceeded);
+        match nimber {
+            Some(nimber) => Ok(nimber),
+            None if depth_exceeded => Err(EvalError::DepthExceeded),
+            None => Err(self.cancelled()),
+        }
+    }
+    /// sets a hook which gets called every `interval` while a nimber is calculated
+    /// the hook runs on the evaluating thread between two slices of the evaluation,
+    /// so nothing is borrowed or locked and the evaluator can be inspected or saved freely,
+    /// stopping the evaluator from within the hook cancels the evaluation resumably
+    pub fn set_checkpoint_hook(&mut self, interval: Duration, hook: CheckpointHook<G>) {
+        self.checkpoint.0 = Some((interval, hook));
+    }
+    /// evaluates in slices of `interval` and calls the hook after every slice which ran out of time
+    fn get_bounded_nimber_with_checkpoints(
+        &mut self,
+        parts: &Parts,
+        bound: usize,
+        interval: Duration,
+        hook: &CheckpointHook<G>,
+    ) -> Option<usize> {
+        //the iterators outlive the slices, but not the query
+        let mut iterators = MoveIterators::default();
+        loop {
+            self.deadline = Some(Instant::now() + interval);
+            let nimber = self.get_bounded_nimber_by_parts(parts, bound, &mut iterators);
+            self.deadline = None;
+            if nimber.is_some() || !self.interrupted.0 || self.is_cancelled() {
+                return nimber;
+            }
+            hook(self);
+            self.interrupted.0 = false;
+        }
+    }
+    /// whether the nimber of the game is already in the cache
+    pub fn is_known(&self, g: &G) -> bool {
+        self.index_map
+            .get(&g.clone().canonical())
+            .is_some_and(|index| self.data[*index].get_nimber().is_some())
+    }
+    /// whether the game has an entry in memory, no matter if its nimber is known already
+    pub fn contains(&self, g: &G) -> bool {
+        self.index_map.contains_key(&g.clone().canonical())
+    }
+    /// the candidate nimbers of a cached position which got ruled out by a move reaching them, in the order they were reached
+    /// candidates dropped by a bound, by the number of moves or by confirming the nimber are not listed,
+    /// `None` if the position is not in the cache or only its nimber is kept
+    pub fn ruled_out_nimbers(&self, g: &G) -> Option<RuledOutNimbers> {
+        let entry = &self.data[*self.index_map.get(&g.clone().canonical())?];
+        if entry.is_evicted() {
+            return None;
+        }
+        Some(RuledOutNimbers {
+            ruled_out: entry.get_ruled_out().to_vec(),
+            nimber: entry.get_nimber(),
+        })
+    }
+    /// the nimber of a game if the nimbers of all of its parts are cached, including spilled ones
+    /// unlike `get_nimber` this never evaluates anything or adds entries
+    pub fn get_cached_nimber(&self, g: &G) -> Option<usize> {
+        self.get_cached_nimber_of_parts(g.clone().get_parts())
+    }
+    /// the xor of the cached nimbers of the given games, decomposing them like `add_parts`
+    fn get_cached_nimber_of_parts(&self, games: Vec<G>) -> Option<usize> {
+        let mut nimber = 0;
+        for game in games {
+            nimber ^= if let Some(heap) = game.as_nim_heap() {
+                heap
+            } else if let Some(components) = game.decompose() {
+                self.get_cached_nimber_of_parts(components)?
+            } else {
+                let game = game.canonical();
+                match self.index_map.get(&game) {
+                    Some(index) => self.data[*index].get_nimber()?,
+                    //a spill which can't be read is treated like one which doesn't know the game
+                    None => self.spill.as_ref()?.load(&game).ok()??,
+                }
+            };
+        }
+        Some(nimber)
+    }
+    /// the cache as a graphviz graph, every entry is a node labeled with its game and its nimber or "?",
+    /// positions have an edge to every part of their moves which is in the cache
+    /// only positions whose moves got generated have edges and evicted entries only show their nimber
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph positions {\n");
+        dot.push_str("    // only positions whose moves got generated have edges\n");
+        for (index, entry) in self.data.iter().enumerate() {
+            let nimber = entry.get_nimber().map_or("?".to_string(), |nimber| nimber.to_string());
+            let game = if entry.is_evicted() { "evicted".to_string() } else { entry.get_game().label() };
+            dot.push_str(&format!("    n{} [label=\"{}: {}\"];\n", index, game.replace('"', "\\\""), nimber));
+        }
+        for (index, entry) in self.data.iter().enumerate() {
+            if entry.is_evicted() || !entry.are_move_indices_generated() {
+                continue;
+            }
+            let mut children = vec![];
+            for _move in entry.get_unique_moves() {
+                self.add_cached_part_indices(_move.get_parts(), &mut children);
+            }
+            children.sort_unstable();
+            children.dedup();
+            for child in children {
+                dot.push_str(&format!("    n{} -> n{};\n", index, child));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+    /// collects the indices of the cached parts of the given games, decomposing them like `add_parts`
+    fn add_cached_part_indices(&self, games: Vec<G>, indices: &mut Vec<usize>) {
+        for game in games {
+            if game.as_nim_heap().is_some() {
+                continue;
+            } else if let Some(components) = game.decompose() {
+                self.add_cached_part_indices(components, indices);
+            } else if let Some(index) = self.index_map.get(&game.canonical()) {
+                indices.push(*index);
+            }
+        }
+    }
+    /// forgets every cached nimber, for example after the rules of the games changed
+    /// statistics like the peak of processing entries are kept
+    /// a spill shared with clones is left to them, this evaluator forgets the nimbers it evicts from then on
+    pub fn clear_cache(&mut self) {
+        self.data.clear();
+        self.ceiling_exceeded = false;
+        self.bound_violation = false;
+        self.done_log.0.clear();
+        self.index_map.clear();
+        self.misere_outcomes.clear();
+        self.remoteness.clear();
+        self.processing_entries = 0;
+        self.done_entries = 0;
+        self.orphans.clear();
+        self.max_nimber = None;
+        if let Some(Err(error)) = self.spill.as_mut().map(Spill::clear) {
+            self.spill_failed(error);
+        }
+        #[cfg(debug_assertions)]
+        self.move_hashes.0.clear();
+    }
+    /// forgets the cached nimber of a single game, returns whether it was cached
+    /// this is safe even while other entries are processed, the game just gets a new entry once it is needed again
+    /// entries which already refer to the old entry keep using its nimber, use `clear_cache` to forget those as well
+    /// the old entry is no longer part of the cache, so it is neither counted in the stats nor listed with the nimbers
+    pub fn invalidate(&mut self, g: &G) -> bool {
+        //misère outcomes are stored for whole games as they were queried
+        let misere = self.misere_outcomes.remove(g).is_some() | self.remoteness.remove(g).is_some();
+        let g = &g.clone().canonical();
+        #[cfg(debug_assertions)]
+        self.move_hashes.0.remove(g);
+        let spilled = match self.spill.as_ref().map(|spill| spill.remove(g)) {
+            Some(Ok(spilled)) => spilled,
+            //the game might still be spilled, so the spill must not be asked for it anymore
+            Some(Err(error)) => {
+                self.spill_failed(error);
+                true
+            }
+            None => false,
+        };
+        let Some(index) = self.index_map.remove(g) else {
+            return spilled || misere;
+        };
+        self.orphans.insert(index);
+        //the old entry must not be spilled anymore, it would shadow the new one
+        if let Some(spill) = self.spill.as_mut() {
+            spill.done_indices.retain(|other| *other != index);
+            spill.referenced.remove(&index);
+        }
+        true
+    }
+    /// calculates the nimber of a game but gives up once the timeout passed,
+    /// in that case the bounds of the nimber known so far are returned
+    /// the progress is kept, so asking again continues where the evaluation stopped
+    pub fn get_nimber_or_estimate(&mut self, g: G, timeout: Duration) -> NimberResult {
+        let parts = self.get_query_parts(g);
+        self.deadline = Some(Instant::now() + timeout);
+        let nimber = self.get_bounded_nimber_by_parts(&parts, usize::MAX, &mut MoveIterators::default());
+        self.deadline = None;
+        match nimber {
+            Some(nimber) => NimberResult::Exact(nimber),
+            None => self.estimate_nimber(&parts),
+        }
+    }
+    /// calculates the nimber of a game but gives up once the timeout passed, without any monitoring thread
+    /// the partially processed entries are kept, so asking again continues where the evaluation stopped
+    /// returns `None` if the timeout passed or the evaluation got cancelled
+    pub fn get_nimber_with_timeout(&mut self, g: G, timeout: Duration) -> Option<usize> {
+        match self.get_nimber_or_estimate(g, timeout) {
+            NimberResult::Exact(nimber) => Some(nimber),
+            NimberResult::Bounds { .. } => None,
+        }
+    }
+    /// calculates the nimber of a game and reports the cache stats every `interval`
+    /// the evaluation runs in slices of `interval`, `callback` is called after every slice,
+    /// including a final time right before returning
+    /// returns `None` if the evaluation got cancelled
+    pub fn get_nimber_with_progress(
+        &mut self,
+        g: G,
+        interval: Duration,
+        mut callback: impl FnMut(CacheStats),
+    ) -> Option<usize> {
+        let parts = self.get_query_parts(g);
+        let mut iterators = MoveIterators::default();
+        loop {
+            self.deadline = Some(Instant::now() + interval);
+            let nimber = self.get_bounded_nimber_by_parts(&parts, usize::MAX, &mut iterators);
+            self.deadline = None;
+            callback(self.get_cache_stats());
+            if nimber.is_some() || self.is_cancelled() {
+                return nimber;
+            }
+        }
+    }
+    /// calculates the nimber of a game on the calling thread without ever reading the clock, e.g. for wasm
+    /// `poll` gets the cache stats after every slice of `COOPERATIVE_SLICE` move evaluations,
+    /// slices can run longer by up to the cancellation poll interval, see `set_cancellation_poll_interval`
+    /// and cancels the evaluation by returning `false`, the progress is kept in that case
+    /// returns `None` if the evaluation got cancelled
+    pub fn get_nimber_cooperative(&mut self, g: G, mut poll: impl FnMut(CacheStats) -> bool) -> Option<usize> {
+        let parts = self.get_query_parts(g);
+        let mut iterators = MoveIterators::default();
+        loop {
+            self.move_budget = Some(self.metrics.move_evaluations + COOPERATIVE_SLICE);
+            let nimber = self.get_bounded_nimber_by_parts(&parts, usize::MAX, &mut iterators);
+            self.move_budget = None;
+            if nimber.is_some() || self.is_cancelled() || !poll(self.get_cache_stats()) {
+                return nimber;
+            }
+        }
+    }
+    /// calculates the nimber of a game while printing the cache stats every 100ms
+    pub fn print_nimber_and_stats(&mut self, g: G) -> Option<usize> {
+        self.get_nimber_with_progress(g, Duration::from_millis(100), |stats| println!("{}", stats))
+    }
+    /// the bounds of the nimber of a sum which are known without any further evaluation
+    fn estimate_nimber(&self, parts: &Parts) -> NimberResult {
+        let bounds: Vec<(usize, usize)> = parts
+            .indices
+            .iter()
+            .map(|index| self.data[*index].get_nimber_bounds())
+            .collect();
+        if let ([(lower, upper)], 0) = (&bounds[..], parts.modifier) {
+            return NimberResult::Bounds { lower: *lower, upper: *upper };
+        }
+        //the xor of the parts can not have a higher bit set than any of the parts
+        let bits = bounds.iter().fold(parts.modifier, |bits, (_, upper)| bits | upper);
+        let upper = if bits == 0 { 0 } else { usize::MAX >> bits.leading_zeros() };
+        NimberResult::Bounds { lower: 0, upper }
+    }
+    /// calculates the nimbers of the games `make(key)` for keys of any kind, e.g. the dimensions of a board
+    /// `progress` gets called with every key as soon as its nimber is known
+    /// if the evaluation gets cancelled only the nimbers calculated so far are returned
+    pub fn tabulate_grid<K: Ord + Clone>(
+        &mut self,
+        keys: impl IntoIterator<Item = K>,
+        make: impl Fn(&K) -> G,
+        mut progress: impl FnMut(&K),
+    ) -> BTreeMap<K, usize> {
+        let mut nimbers = BTreeMap::new();
+        for key in keys {
+            let Ok(nimber) = self.try_get_nimber(make(&key)) else {
+                break;
+            };
+            progress(&key);
+            nimbers.insert(key, nimber);
+        }
+        nimbers
+    }
+    /// calculates the nimber of the sum of the given games
+    /// returns `None` if the evaluation got cancelled
+    pub fn get_nimber_by_parts(&mut self, games: Vec<G>) -> Option<usize> {
+        self.reclaim_evicted_entries();
+        let mut parts = Parts::default();
+        for game in games {
+            let game_parts = self.get_parts_of(game);
+            parts.indices.extend(game_parts.indices);
+            parts.modifier ^= game_parts.modifier;
+        }
+        parts.indices = remove_pairs(parts.indices);
+        self.get_bounded_nimber_by_parts(&parts, usize::MAX, &mut MoveIterators::default())
+    }
+    /// explains the nimber of a game by listing a move to every smaller nimber
+    /// together with the nimbers of all moves, which shows that the nimber itself is not reachable
+    /// the nimbers of the moves are calculated from their parts, just like during the evaluation
+    /// returns `None` if the evaluation got cancelled
+    pub fn explain_nimber(&mut self, g: G) -> Option<NimberProof<G>> {
+        let nimber = self.try_get_nimber(g.clone()).ok()?;
+        let mut witnesses: Vec<Option<G>> = vec![None; nimber];
+        let mut move_nimbers = vec![];
+        for _move in g.get_unique_moves() {
+            let move_nimber = self.try_get_nimber(_move.clone()).ok()?;
+            if let Some(witness @ None) = witnesses.get_mut(move_nimber) {
+                *witness = Some(_move.clone());
+            }
+            move_nimbers.push((_move, move_nimber));
+        }
+        Some(NimberProof {
+            nimber,
+            witnesses: witnesses
+                .into_iter()
+                .map(|witness| witness.expect("every smaller nimber is reachable"))
+                .collect(),
+            move_nimbers,
+        })
+    }
+    /// the sorted nimbers of all moves of a game, the nimber of the game is their mex
+    /// every move is evaluated completely, so this costs more than `get_nimber`
+    /// which stops as soon as the bounds settle the nimber
+    /// returns `None` if the evaluation got cancelled
+    pub fn reachable_nimbers(&mut self, g: G) -> Option<Vec<usize>> {
+        let mut nimbers = g
+            .get_unique_moves()
+            .into_iter()
+            .map(|_move| self.try_get_nimber(_move).ok())
+            .collect::<Option<Vec<usize>>>()?;
+        nimbers.sort_unstable();
+        nimbers.dedup();
+        Some(nimbers)
+    }
+    /// checks that the nimber of a game equals the nimber of the sum `decompose` splits it into
+    /// this cross validates the evaluation of a single position against the evaluation of a sum
+    /// returns the agreed nimber, the mismatch or `None` if the evaluation got cancelled
+    pub fn verify_decomposition(
+        &mut self,
+        g: G,
+        decompose: impl FnOnce(&G) -> Vec<G>,
+    ) -> Option<Result<usize, DecompositionMismatch>> {
+        let parts = decompose(&g);
+        let whole = self.try_get_nimber(g).ok()?;
+        let by_parts = self.get_nimber_by_parts(parts)?;
+        if whole == by_parts {
+            Some(Ok(whole))
+        } else {
+            Some(Err(DecompositionMismatch { whole, by_parts }))
+        }
+    }
+    /// calculates whether the game is a P or an N position
+    /// this is cheaper than calculating the nimber since it only needs to rule out 0
+    pub fn get_outcome(&mut self, g: G) -> Outcome {
+        match self.get_bounded_nimber(g, 0) {
+            Some(0) => Outcome::P,
+            _ => Outcome::N,
+        }
+    }
+    /// calculates whether the game is a P or an N position like `get_outcome`
+    pub fn try_get_outcome(&mut self, g: G) -> Result<Outcome, EvalError> {
+        match self.try_get_bounded_nimber(g, 0) {
+            Ok(0) => Ok(Outcome::P),
+            Ok(_) | Err(EvalError::BoundExceeded) => Ok(Outcome::N),
+            Err(error) => Err(error),
+        }
+    }
+    /// whether the game is a P position, i.e. whether its nimber is 0
+    /// an N position is confirmed as soon as a move to a P position is found,
+    /// confirming a P position still needs every move to be ruled out
+    /// returns `None` if the evaluation got cancelled
+    pub fn is_p_position(&mut self, g: G) -> Option<bool> {
+        Some(self.try_get_outcome(g).ok()? == Outcome::P)
+    }
+    /// calculates the outcome of a game under misère play, where the player who can not move wins
+    /// misère outcomes of parts do not combine like nimbers, so the game is never split into its parts
+    /// and `get_unique_moves` of the whole game has to list all of its moves
+    /// returns `None` if the evaluation got cancelled
+    pub fn get_misere_outcome(&mut self, g: G) -> Option<Outcome> {
+        if let Some(outcome) = self.misere_outcomes.get(&g) {
+            return Some(*outcome);
+        }
+        let moves = g.get_unique_moves();
+        //without any moves the player to move has already won
+        let mut outcome = if moves.is_empty() { Outcome::N } else { Outcome::P };
+        for _move in moves {
+            if self.is_cancelled() {
+                return None;
+            }
+            if self.get_misere_outcome(_move)? == Outcome::P {
+                outcome = Outcome::N;
+                break;
+            }
+        }
+        self.misere_outcomes.insert(g, outcome);
+        Some(outcome)
+    }
+    /// calculates the remoteness of a game, the number of moves until the game ends if the winner hurries and the loser delays
+    /// a game without moves has remoteness 0, a game with a move to an even remoteness wins and has the smallest such remoteness plus 1,
+    /// any other game loses and has the largest remoteness of its moves plus 1, so P positions are exactly those with even remoteness
+    /// remotenesses of parts do not combine like nimbers, so like for misère play `get_unique_moves` of the whole game is used
+    /// returns `None` if the evaluation got cancelled
+    pub fn get_remoteness(&mut self, g: G) -> Option<usize> {
+        if let Some(remoteness) = self.remoteness.get(&g) {
+            return Some(*remoteness);
+        }
+        let mut winning = None;
+        let mut losing = None;
+        for _move in g.get_unique_moves() {
+            if self.is_cancelled() {
+                return None;
+            }
+            let remoteness = self.get_remoteness(_move)?;
+            if remoteness.is_multiple_of(2) {
+                winning = Some(winning.map_or(remoteness, |winning: usize| winning.min(remoteness)));
+            } else {
+                losing = losing.max(Some(remoteness));
+            }
+        }
+        let remoteness = winning.or(losing).map_or(0, |remoteness| remoteness + 1);
+        self.remoteness.insert(g, remoteness);
+        Some(remoteness)
+    }
+    /// finds a move to a P position, which wins the game
+    /// the moves are evaluated on demand, each only as far as needed to rule out nimber 0
+    /// returns `None` if the game is a P position itself or the evaluation got cancelled
+    pub fn get_winning_move(&mut self, g: G) -> Option<G> {
+        for _move in g.get_unique_moves() {
+            if self.try_get_outcome(_move.clone()).ok()? == Outcome::P {
+                return Some(_move);
+            }
+        }
+        None
+    }
+    /// calculates the outcomes of the games `make(i)` for every `i` in the range
+    /// the bit of a game is set iff it is a P position
+    pub fn export_outcome_bitset(&mut self, make: impl Fn(usize) -> G, range: Range<usize>) -> BitVec {
+        range
+            .map(|i| self.get_outcome(make(i)) == Outcome::P)
+            .collect()
+    }
+    /// calculates the nimbers of `make(0)` up to `make(n)` bottom up,
+    /// so every game can reuse the nimbers of the smaller ones
+    /// `progress` gets called with every index as soon as its nimber is known
+    /// if the evaluation gets cancelled only the nimbers calculated so far are returned
+    pub fn get_nimber_sequence(
+        &mut self,
+        make: impl Fn(usize) -> G,
+        n: usize,
+        mut progress: impl FnMut(usize),
+    ) -> Vec<usize> {
+        self.nimber_sequence(make)
+            .take(n + 1)
+            .map_while(|nimber| nimber)
+            .enumerate()
+            .map(|(i, nimber)| {
+                progress(i);
+                nimber
+            })
+            .collect()
+    }
+    /// lazily yields the nimbers of `make(0)`, `make(1)`, ... one after another,
+    /// so every game can reuse the nimbers of the smaller ones
+    /// if the evaluation gets cancelled `None` is yielded and the sequence ends
+    pub fn nimber_sequence<'a>(
+        &'a mut self,
+        make: impl Fn(usize) -> G + 'a,
+    ) -> impl Iterator<Item = Option<usize>> + 'a {
+        let mut cancelled = false;
+        (0..).map_while(move |i| {
+            if cancelled {
+                return None;
+            }
+            let nimber = self.try_get_nimber(make(i)).ok();
+            cancelled = nimber.is_none();
+            Some(nimber)
+        })
+    }
+    /// the first `n` nimbers of the sequence, i.e. of `make(0)` up to `make(n - 1)`, separated by commas like on the oeis
+    /// returns `None` if the evaluation got cancelled
+    pub fn sequence_string(&mut self, make: impl Fn(usize) -> G, n: usize) -> Option<String> {
+        let nimbers: Option<Vec<String>> = self
+            .nimber_sequence(make)
+            .take(n)
+            .map(|nimber| nimber.map(|nimber| nimber.to_string()))
+            .collect();
+        nimbers.map(|nimbers| nimbers.join(", "))
+    }
+    /// evaluates the given games in the given order to fill the cache before a bigger query,
+    /// e.g. the smallest positions first, so the big query needs less recursion
+    /// warming is only an optimization, it never changes any nimber
+    /// returns `false` if the evaluation got cancelled before all games were evaluated
+    pub fn warm(&mut self, games: impl IntoIterator<Item = G>) -> bool {
+        games.into_iter().all(|game| self.try_get_nimber(game).is_ok())
+    }
+    /// calculates the nimber of a game like `get_nimber`, but without nesting the evaluation of the moves
+    /// the positions still to be evaluated are kept on an agenda on the heap and a position is only evaluated
+    /// once the parts of all of its moves are done, so arbitrarily deep games can't overflow the stack
+    /// this evaluates every move completely and generates lazy moves at once, so it pays off only for deep games
+    /// returns `None` if the evaluation got cancelled
+    pub fn get_nimber_iteratively(&mut self, g: G) -> Option<usize> {
+        let parts = self.get_query_parts(g);
+        let mut agenda = parts.indices.clone();
+        let mut iterators = MoveIterators::default();
+        while let Some(&index) = agenda.last() {
+            if self.data[index].get_nimber().is_none() {
+                let pending = self.get_pending_part_indices(index, &mut iterators);
+                if !pending.is_empty() {
+                    agenda.extend(pending);
+                    continue;
+                }
+                //the parts of every move are done, so this only looks a single move deep
+                self.get_bounded_nimber_by_index(index, usize::MAX, &mut iterators)?;
+            }
+            agenda.pop();
+        }
+        self.get_bounded_nimber_by_parts(&parts, usize::MAX, &mut iterators)
+    }
+    /// the parts of the moves of an entry which have no nimber yet, the moves get generated if needed
+    fn get_pending_part_indices(&mut self, index: usize, iterators: &mut MoveIterators<G>) -> Vec<usize> {
+        self.get_move_indices(index, iterators);
+        let mut moves = vec![];
+        while let Some(move_indices) = self.get_next_move_indices(index, iterators) {
+            moves.push(move_indices);
+        }
+        //every lazy move got taken, so the iterator is already gone
+        let pending = moves
+            .iter()
+            .flat_map(|parts| &parts.indices)
+            .filter(|part| self.data[**part].get_nimber().is_none())
+            .copied()
+            .collect();
+        //the moves are queued again in the same order, only their lazy generation ended
+        if self.data[index].are_move_indices_generated() {
+            self.data[index].add_unprocessed_move_indices(moves);
+        }
+        pending
+    }
+    /// calculates the nimber of a position from a precomputed graph instead of generating its moves,
+    /// every position maps to its moves and every move is given by the parts of the position it leads to
+    /// every position reachable from the root has to be in the graph, which is checked before anything is evaluated
+    /// positions whose moves got generated before keep their moves, so the graph has to agree with them
+    /// returns the first missing position or `Ok(None)` if the evaluation got cancelled
+    pub fn get_nimber_from_graph(
+        &mut self,
+        root: &G,
+        graph: &HashMap<G, Vec<Vec<G>>>,
+    ) -> Result<Option<usize>, MissingPosition<G>> {
+        let mut positions = vec![root];
+        let mut reached: HashSet<&G> = HashSet::from([root]);
+        let mut i = 0;
+        while i < positions.len() {
+            let moves = graph.get(positions[i]).ok_or_else(|| MissingPosition(positions[i].clone()))?;
+            for part in moves.iter().flatten() {
+                if reached.insert(part) {
+                    positions.push(part);
+                }
+            }
+            i += 1;
+        }
+        self.reclaim_evicted_entries();
+        for position in positions {
+            let index = self.get_index_of(position);
+            if self.data[index].get_nimber().is_some() || self.data[index].are_move_indices_generated() {
+                continue;
+            }
+            let move_indices = graph[position]
+                .iter()
+                .map(|parts| {
+                    let mut move_indices = Parts::default();
+                    self.add_parts(parts.clone(), &mut move_indices);
+                    move_indices.indices = remove_pairs(move_indices.indices);
+                    move_indices
+                })
+                .collect();
+            self.set_move_indices(index, move_indices);
+        }
+        let index = self.get_index_of(root);
+        self.interrupted.0 = false;
+        Ok(self.get_bounded_nimber_by_index(index, usize::MAX, &mut MoveIterators::default()))
+    }
+    /// calculates the nimbers of `make(0)` up to `make(n)` and searches them for a period, see `find_period`
+    /// returns `None` if no period is confirmed or the evaluation got cancelled
+    pub fn get_period(&mut self, make: impl Fn(usize) -> G, n: usize) -> Option<(usize, usize)> {
+        let nimbers = self.get_nimber_sequence(make, n, |_| {});
+        if nimbers.len() <= n {
+            return None;
+        }
+        find_period(&nimbers)
+    }
+    /// calculates the nimbers of the sequence `make(0), make(1), ...` in chunks, starting with `start` nimbers
+    /// and adding `step` nimbers at a time, until `find_period` confirms a period or `max` nimbers are reached
+    /// returns `None` if no period is confirmed within `max` nimbers or the evaluation got cancelled
+    pub fn extend_until_periodic(
+        &mut self,
+        make: impl Fn(usize) -> G,
+        start: usize,
+        step: usize,
+        max: usize,
+    ) -> Option<(usize, usize)> {
+        assert!(step > 0, "the sequence has to grow between two searches");
+        let mut nimbers = vec![];
+        let mut len = start.min(max);
+        loop {
+            for i in nimbers.len()..len {
+                nimbers.push(self.try_get_nimber(make(i)).ok()?);
+            }
+            if let Some(period) = find_period(&nimbers) {
+                return Some(period);
+            }
+            if len == max {
+                return None;
+            }
+            len = len.saturating_add(step).min(max);
+        }
+    }
+    /// calculates the table of the nimbers of `make(0)` up to `make(n)`, see `get_nimber_sequence`
+    pub fn solve_single_heap_table(
+        &mut self,
+        make: impl Fn(usize) -> G,
+        n: usize,
+        progress: impl FnMut(usize),
+    ) -> NimberTable {
+        NimberTable::from(self.get_nimber_sequence(make, n, progress))
+    }
+    /// seeds the outcomes of a bitset created by `export_outcome_bitset` with the same `make` and range
+    /// P positions become done with nimber 0, N positions just lose 0 as a possible nimber
+    /// games consisting of more than one part are skipped since the outcome of a sum
+    /// does not tell anything about its parts
+    pub fn load_outcome_bitset(&mut self, make: impl Fn(usize) -> G, range: Range<usize>, bits: &BitSlice) {
+        assert_eq!(range.len(), bits.len(), "the bitset has to cover the range");
+        self.reclaim_evicted_entries();
+        for (i, is_p_position) in range.zip(bits.iter().by_vals()) {
+            let [index] = self.get_part_indices(make(i))[..] else {
+                continue;
+            };
+            if self.data[index].get_nimber().is_some() {
+                continue;
+            }
+            if is_p_position {
+                self.data[index].set_nimber(0);
+            } else {
+                self.data[index].remove_nimber(0);
+            }
+            if self.data[index].get_nimber().is_some() {
+                self.entry_done(index);
+            }
+        }
     }
-    fn try_rule_out_smallest_possible_nimber(&mut self, index: usize) {
-        self.get_move_indices(index);
+    fn try_rule_out_smallest_possible_nimber(&mut self, index: usize, iterators: &mut MoveIterators<G>) {
+        self.get_move_indices(index, iterators);
+        //the number of moves alone might already determine the nimber
+        if self.data[index].get_nimber().is_some() {
+            return;
+        }
         let nimber = self.data[index].get_smallest_possible_nimber();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            "rule_out",
+            game = %self.data[index].get_game().label(),
+            candidate = nimber,
+            moves = self.data[index].get_unprocessed_move_count()
+        )
+        .entered();
+
+        //the queried positions hand their moves to the threads first, bounded by the candidate,
+        //the loop below then finds the nimbers of the moves in the cache, or the progress the threads made on them
+        //nested positions keep evaluating serially, their moves are mostly covered by the threads already
+        if let Some(parallel_evaluation) = self.parallel_evaluation.0.clone().filter(|_| self.depth == 1) {
+            let moves: Vec<(Parts, usize)> = self.data[index]
+                .get_unprocessed_move_indices()
+                .map(|parts| (parts.clone(), nimber))
+                .collect();
+            if moves.len() > 1 {
+                parallel_evaluation(self, &moves, Some(nimber), iterators);
+            }
+        }
 
         let mut still_unprocessed_move_indices = vec![];
-        
-        while let Some(move_indices) = self.data[index].get_next_unprocessed_move_index() {
-            match self.get_bounded_nimber_by_parts(&move_indices, nimber) {
+
+        //the entry is looked up by its index again after every move, no borrow of the cache is held
+        //while the moves are evaluated, so the recursion may freely add entries or reallocate the cache
+        while let Some(move_indices) = self.get_next_move_indices(index, iterators) {
+            self.metrics.move_evaluations += 1;
+            match self.get_bounded_nimber_by_parts(&move_indices, nimber, iterators) {
                 Some(move_nimber) => {
-                    self.data[index].remove_nimber(move_nimber);
-                    if move_nimber == nimber {
+                    self.data[index].rule_out(move_nimber);
+                    //the remaining moves only need to be scanned if the candidate is still in doubt,
+                    //a candidate which is the only possible nimber left is confirmed without them
+                    if move_nimber == nimber || self.data[index].get_nimber().is_some() {
                         self.data[index].add_unprocessed_move_indices(still_unprocessed_move_indices);
+                        #[cfg(feature = "tracing")]
+                        match self.data[index].get_nimber() {
+                            Some(nimber) => tracing::trace!(nimber, "confirmed"),
+                            None => tracing::trace!(nimber, "ruled out"),
+                        }
+                        if let Some(confirmed) = self.data[index].get_nimber() {
+                            self.check_unscanned_moves(index, confirmed);
+                            self.entry_done(index);
+                        }
                         return;
                     }
                 }
                 //since the move was not fully prcessed we need to add it back to the unprocessed moves later
                 None => {
                     still_unprocessed_move_indices.push(move_indices);
+                    //the move might only be unfinished because the evaluation got cancelled
+                    if self.interrupted.0 {
+                        self.data[index].add_unprocessed_move_indices(still_unprocessed_move_indices);
+                        return;
+                    }
                 },
             }
         }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(nimber, "confirmed");
         self.data[index].set_nimber(nimber);
+        self.entry_done(index);
+    }
+    /// the highest number of entries that were processed simultaneously
+    /// top down evaluations have a high peak, bottom up sweeps a low one
+    pub fn peak_processing_entries(&self) -> usize {
+        self.peak_processing_entries
+    }
+    /// the number of moves evaluated so far
+    pub fn move_evaluations(&self) -> usize {
+        self.metrics.move_evaluations
+    }
+    /// the counters of the work done since the evaluator got created or the metrics got reset
+    pub fn metrics(&self) -> Metrics {
+        self.metrics
+    }
+    /// resets the metrics, e.g. to measure a single evaluation
+    pub fn reset_metrics(&mut self) {
+        self.metrics = Metrics::default();
+    }
+    /// sets the order in which moves are visited, the order only applies to moves generated afterwards
+    /// the same order visits the moves of the same game identically in every run
+    pub fn set_move_order(&mut self, order: MoveOrder<G>) {
+        self.move_order.0 = order;
+    }
+    /// sets a callback which gets called whenever an entry gets a nimber higher than all nimbers before
+    pub fn on_new_max_nimber(&mut self, callback: NimberCallback<G>) {
+        self.on_new_max_nimber.0 = Some(callback);
+    }
+    /// sets a callback which gets called exactly once for every entry when its nimber becomes known,
+    /// e.g. to stream the nimbers somewhere while they are calculated
+    /// entries reloaded from the spill or merged from other evaluators are reported as well
+    pub fn set_on_done(&mut self, callback: NimberCallback<G>) {
+        self.on_done.0 = Some(callback);
+    }
+    /// the games which got a nimber since the given generation together with their nimbers and the current generation
+    /// pass 0 the first time and the returned generation afterwards to only get the new nimbers,
+    /// this only costs as much as the number of new nimbers
+    /// entries which got evicted since are skipped, since only their nimber is kept,
+    /// and the log is not saved, so a loaded evaluator starts with an empty log
+    pub fn nimbers_since(&self, generation: u64) -> (Vec<(G, usize)>, u64) {
+        let start = self.done_log.0.partition_point(|(done, _)| *done < generation);
+        let nimbers = self.done_log.0[start..]
+            .iter()
+            .map(|(_, index)| index)
+            .filter(|index| !self.orphans.contains(index))
+            .map(|index| &self.data[*index])
+            .filter(|entry| !entry.is_evicted())
+            .map(|entry| (entry.get_game().clone(), entry.get_nimber().unwrap()))
+            .collect();
+        (nimbers, self.next_generation.0)
+    }
+    /// the cached games with a known nimber in the order they got added, evicted entries are skipped
+    /// the games are only cloned while iterating, the iterator borrows the evaluator, so it can't change meanwhile
+    pub fn iter_nimbers(&self) -> impl Iterator<Item = (G, usize)> + '_ {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.orphans.contains(index))
+            .map(|(_, entry)| entry)
+            .filter(|entry| !entry.is_evicted())
+            .filter_map(|entry| Some((entry.get_game().clone(), entry.get_nimber()?)))
+    }
+    /// collects `iter_nimbers`
+    pub fn get_nimbers(&self) -> Vec<(G, usize)> {
+        self.iter_nimbers().collect()
+    }
+    /// an independent copy of the cache, e.g. to measure evaluations repeatedly from the same warm cache
+    /// unlike a clone the copy has its own cancellation token, starts with fresh metrics and keeps no callbacks or limits
+    /// a spill is still shared, since the nimbers of the evicted entries are only kept there
+    pub fn fork_cache(&self) -> Evaluator<G> {
+        let mut fork = self.clone();
+        fork.cancellation_token = Transient::default();
+        fork.metrics = Metrics::default();
+        fork.peak_processing_entries = fork.processing_entries;
+        fork.deadline = None;
+        fork.move_budget = None;
+        fork.checkpoint.0 = None;
+        fork.on_new_max_nimber.0 = None;
+        fork.on_done.0 = None;
+        fork.parallel_evaluation.0 = None;
+        fork
+    }
+    /// whether a position got a nimber above its `Impartial::get_max_nimber` or a nimber confirmed by the max nimbers
+    /// turned out to be reachable by a move, debug builds panic right away instead
+    /// the max nimbers prune the evaluation, so after a violation the nimbers can't be trusted until the cache is cleared
+    pub fn had_bound_violation(&self) -> bool {
+        self.bound_violation
+    }
+    /// counts the entries in each state of the evaluation
+    pub fn get_cache_stats(&self) -> CacheStats {
+        let mut stats = CacheStats {
+            stubs: self.data.len() - self.processing_entries - self.done_entries,
+            processing: self.processing_entries,
+            done: self.done_entries,
+        };
+        //the orphans change their state like any other entry, so they are only left out when counting
+        for index in &self.orphans {
+            let entry = &self.data[*index];
+            match (entry.get_nimber(), entry.are_move_indices_generated()) {
+                (Some(_), _) => stats.done -= 1,
+                (None, true) => stats.processing -= 1,
+                (None, false) => stats.stubs -= 1,
+            }
+        }
+        stats
+    }
+    /// estimates the bytes taken by the cache, i.e. the entries with their moves and the index map
+    /// the heap data of the games is only included as far as `Impartial::heap_size` reports it
+    pub fn estimated_memory_bytes(&self) -> usize {
+        let entries = self.data.capacity() * size_of::<Entry<G>>()
+            + self.data.iter().map(|entry| entry.heap_size()).sum::<usize>();
+        //every slot of the index map holds a key, a value and a control byte
+        let index_map = self.index_map.capacity() * (size_of::<Arc<G>>() + size_of::<usize>() + 1);
+        entries + index_map
+    }
+    /// gets called exactly once for every entry when its nimber becomes known
+    fn entry_done(&mut self, index: usize) {
+        if self.data[index].are_move_indices_generated() {
+            self.processing_entries -= 1;
+        }
+        self.done_entries += 1;
+        let nimber = self.data[index].get_nimber().unwrap();
+        let orphan = self.orphans.contains(&index);
+        if !orphan {
+            self.done_log.0.push((self.next_generation.0, index));
+            self.next_generation.0 += 1;
+        }
+        if let Some(callback) = &self.on_done.0 {
+            callback(self.data[index].get_game(), nimber);
+        }
+        if self.nimber_ceiling.is_some_and(|ceiling| nimber > ceiling) {
+            self.ceiling_exceeded = true;
+        }
+        if !self.data[index].is_evicted() {
+            let game = self.data[index].get_game();
+            let max_nimber = game.get_max_nimber();
+            if nimber > max_nimber {
+                let message = format!("the nimber {} of {} exceeds its max nimber {}", nimber, game.label(), max_nimber);
+                self.report_bound_violation(message);
+            }
+        }
+        if let Some(worker) = self.shared.0.as_ref().filter(|_| !self.data[index].is_evicted()) {
+            worker.cache.publish(self.data[index].get_shared_game(), nimber);
+        }
+        if self.max_nimber.is_none_or(|max_nimber| nimber > max_nimber) {
+            self.max_nimber = Some(nimber);
+            if let Some(callback) = &self.on_new_max_nimber.0 {
+                callback(self.data[index].get_game(), nimber);
+            }
+        }
+        //evicting an orphan would remove the key of the entry which replaced it
+        if let Some(spill) = self.spill.as_mut().filter(|_| !orphan) {
+            spill.done_indices.push_back(index);
+            self.spill_done_entries();
+        }
+    }
+    /// a nimber confirmed because no other candidate was left relies on the max nimbers of the game and its moves,
+    /// a move which was not scanned but is known to have the same nimber shows that one of them was too low
+    fn check_unscanned_moves(&mut self, index: usize, nimber: usize) {
+        let is_reachable = self.data[index].get_unprocessed_move_indices().any(|parts| {
+            parts
+                .indices
+                .iter()
+                .try_fold(parts.modifier, |xor, part| Some(xor ^ self.data[*part].get_nimber()?))
+                == Some(nimber)
+        });
+        if is_reachable {
+            let game = self.data[index].get_game();
+            let message = format!("a move of {} has its nimber {} as well, a max nimber is too low", game.label(), nimber);
+            self.report_bound_violation(message);
+        }
+    }
+    /// remembers that the max nimbers pruned the evaluation wrongly, debug builds panic right away
+    fn report_bound_violation(&mut self, message: String) {
+        self.bound_violation = true;
+        if cfg!(debug_assertions) {
+            panic!("{}", message);
+        }
+    }
+    /// protects a done entry from the next eviction
+    fn mark_used(&mut self, index: usize) {
+        if let Some(spill) = self.spill.as_mut() {
+            let entry = &self.data[index];
+            if entry.get_nimber().is_some() && !entry.is_evicted() && !self.orphans.contains(&index) {
+                spill.referenced.insert(index);
+            }
+        }
+    }
+    /// moves the least recently used done entries to the spill
+    /// until at most `limit` entries are kept in memory
+    fn spill_done_entries(&mut self) {
+        let Some(spill) = self.spill.as_mut() else {
+            return;
+        };
+        while self.data.len() - spill.spilled > spill.limit {
+            let Some(index) = spill.done_indices.pop_front() else {
+                return;
+            };
+            //entries used again since they were queued get a second chance, which approximates lru
+            if spill.referenced.remove(&index) {
+                spill.done_indices.push_back(index);
+                continue;
+            }
+            //the evicted entry keeps its nimber, so indices refering to it stay valid
+            let nimber = self.data[index].get_nimber().unwrap();
+            if let Err(error) = spill.store(self.data[index].get_game(), nimber) {
+                //the entry stays in memory, the spill is replaced by one discarding the evicted nimbers
+                spill.done_indices.push_front(index);
+                self.spill_failed(error);
+                return;
+            }
+            let game = self.data[index].evict();
+            self.index_map.remove(&*game);
+            spill.spilled += 1;
+        }
+    }
+    /// remembers a failure of the spill and stops using it
+    /// the failure cancels the running evaluation, unless it is one of the infallible api
+    fn spill_failed(&mut self, error: io::Error) {
+        if let Some(spill) = self.spill.as_mut() {
+            spill.detach();
+        }
+        self.spill_error.get_or_insert(EvalError::SpillFailed {
+            kind: error.kind(),
+            message: error.to_string(),
+        });
     }
     /// gets bounded nimber given an index
-    fn get_bounded_nimber_by_index(&mut self, index: usize, bound: usize) -> Option<usize> {
+    fn get_bounded_nimber_by_index(&mut self, index: usize, bound: usize, iterators: &mut MoveIterators<G>) -> Option<usize> {
+        let entry = &self.data[index];
+        let needs_expansion = entry.get_nimber().is_none() && entry.get_smallest_possible_nimber() <= bound;
+        let Some(worker) = self.shared.0.clone().filter(|_| needs_expansion) else {
+            return self.expand_until_bounded(index, bound, iterators);
+        };
+        //the entry is claimed for the whole expansion, so other evaluators sharing the cache wait instead of expanding it as well
+        let game = entry.get_shared_game().clone();
+        let claim = match worker.cache.claim(&game, worker.id, || self.is_cancelled()) {
+            Claimed::Claim(claim, progress) => {
+                if let Some(progress) = progress {
+                    self.restore_progress(index, progress, iterators);
+                }
+                claim
+            }
+            Claimed::Done(nimber) => {
+                self.data[index].set_nimber(nimber);
+                self.entry_done(index);
+                return self.expand_until_bounded(index, bound, iterators);
+            }
+            Claimed::Cancelled => {
+                self.interrupted.0 = true;
+                return None;
+            }
+        };
+        let nimber = self.expand_until_bounded(index, bound, iterators);
+        //a done entry published its nimber already
+        if self.data[index].get_nimber().is_none() {
+            claim.release(self.get_progress(index));
+        }
+        nimber
+    }
+    /// expands the entry given by the index until it is done or its nimber is known to exceed the bound
+    fn expand_until_bounded(&mut self, index: usize, bound: usize, iterators: &mut MoveIterators<G>) -> Option<usize> {
+        self.interrupted.0 = false;
+        //a new query checks for a cancellation after its first step
+        if self.depth == 0 {
+            self.steps_since_poll.0 = self.poll_interval;
+        }
+        if self.data[index].get_nimber().is_some() {
+            self.metrics.cache_hits += 1;
+        } else {
+            self.metrics.cache_misses += 1;
+        }
         loop {
             let entry = &self.data[index];
 
             if let Some(nimber) = entry.get_nimber() {
+                self.mark_used(index);
                 return Some(nimber);
             }
             if entry.get_smallest_possible_nimber() > bound {
                 return None;
             }
-            self.try_rule_out_smallest_possible_nimber(index);
+            if self.max_depth.is_some_and(|max_depth| self.depth >= max_depth) {
+                self.depth_exceeded = true;
+                self.interrupted.0 = true;
+                return None;
+            }
+            self.depth += 1;
+            self.try_rule_out_smallest_possible_nimber(index, iterators);
+            self.depth -= 1;
+            //checking for a cancellation reads the clock, so nested positions only check every `poll_interval` steps
+            //while the queried position and positions whose moves got interrupted always check
+            self.steps_since_poll.0 += 1;
+            if self.depth > 0 && !self.interrupted.0 && self.steps_since_poll.0 < self.poll_interval {
+                continue;
+            }
+            //cancellations are only checked after some progress was made,
+            //so evaluations that keep getting cancelled still finish eventually
+            if self.is_cancelled() {
+                //the positions above check right away as well
+                let nimber = self.data[index].get_nimber();
+                self.interrupted.0 = nimber.is_none();
+                return nimber;
+            }
+            self.steps_since_poll.0 = 0;
         }
     }
     /// gets the nimber of a game where the parts are given by the given indices
-    fn get_bounded_nimber_by_parts(&mut self, indices: &Vec<usize>, bound: usize) -> Option<usize> {
-        if indices.len() == 0 {
-            return Some(0);
+    fn get_bounded_nimber_by_parts(&mut self, parts: &Parts, bound: usize, iterators: &mut MoveIterators<G>) -> Option<usize> {
+        let indices = &parts.indices;
+        if indices.is_empty() {
+            return Some(parts.modifier);
+        }
+        //the queried sums and the moves of the queried positions get their parts evaluated side by side first,
+        //the loop below then finds their nimbers in the cache, nested sums are covered by the threads already
+        if let Some(parallel_evaluation) = self.parallel_evaluation.0.clone().filter(|_| self.depth <= 1) {
+            self.evaluate_parts_in_parallel(parts, bound, &parallel_evaluation, iterators);
+        }
+        //the bits the parts after each part could still set, from the current bounds of their nimbers
+        let mut later_bits = vec![0; indices.len()];
+        for i in (0..indices.len() - 1).rev() {
+            later_bits[i] = later_bits[i + 1] | self.data[indices[i + 1]].get_nimber_bounds().1;
+        }
+        let mut modifier = parts.modifier;
+        for (i, index) in indices[0..indices.len() - 1].iter().enumerate() {
+            //a bit of this part above the bound and above every bit the other parts could set can't cancel,
+            //so a part exceeding this bound already makes the sum exceed the bound
+            let bits = bound | modifier | later_bits[i];
+            let part_bound = usize::MAX.checked_shr(bits.leading_zeros()).unwrap_or(0);
+            //without a bound this only fails if the evaluation got cancelled
+            modifier ^= self.get_bounded_nimber_by_index(*index, part_bound, iterators)?;
         }
-        let modifier = indices[0..indices.len() - 1]
-            .iter()
-            .fold(0, |modifier, index| {
-                modifier ^ self.get_bounded_nimber_by_index(*index, usize::MAX).unwrap()
-            });
         //index of the last part of the current child game
         let last_part = indices.last().unwrap();
         //if the last part has the _nimber == nimber xor modifier
-        match self.get_bounded_nimber_by_index(*last_part, bound + modifier) {
-            Some(last_nimber) => Some(last_nimber ^ modifier),
-            None => None,
+        self.get_bounded_nimber_by_index(*last_part, bound.saturating_add(modifier), iterators)
+            .map(|last_nimber| last_nimber ^ modifier)
+    }
+    /// evaluates the unknown parts of a sum side by side, every part up to a bound of its own
+    fn evaluate_parts_in_parallel(
+        &mut self,
+        parts: &Parts,
+        bound: usize,
+        parallel_evaluation: &ParallelEvaluation<G>,
+        iterators: &mut MoveIterators<G>,
+    ) {
+        let mut unknown_parts: Vec<usize> = parts
+            .indices
+            .iter()
+            .copied()
+            .filter(|index| self.data[*index].get_nimber().is_none())
+            .collect();
+        //every part is dispatched once, even if the sum repeats it
+        unknown_parts.sort_unstable();
+        unknown_parts.dedup();
+        if unknown_parts.len() < 2 {
+            return;
+        }
+        //the nimbers of the other parts are not known while the parts are evaluated side by side,
+        //so every part is bounded like in the serial loop, but with the bits all other parts could set at most,
+        //a bit of a part above those and above the bound can't cancel, so a part exceeding its bound still makes the sum exceed the bound
+        let max_bits: Vec<usize> = parts
+            .indices
+            .iter()
+            .map(|index| self.data[*index].get_nimber_bounds().1)
+            .collect();
+        let sums: Vec<(Parts, usize)> = unknown_parts
+            .into_iter()
+            .map(|index| {
+                let bits = parts
+                    .indices
+                    .iter()
+                    .zip(&max_bits)
+                    .filter(|(other, _)| **other != index)
+                    .fold(bound | parts.modifier, |bits, (_, max_bits)| bits | max_bits);
+                let part_bound = usize::MAX.checked_shr(bits.leading_zeros()).unwrap_or(0);
+                (Parts { indices: vec![index], modifier: 0 }, part_bound)
+            })
+            .collect();
+        parallel_evaluation(self, &sums, None, iterators);
+    }
+    /// an upper bound on the nimber of a sum, the xor of the parts has at most as many bits as its largest part
+    fn get_max_nimber_of_parts(&self, parts: &Parts) -> usize {
+        match parts.indices[..] {
+            [] => parts.modifier,
+            [index] if parts.modifier == 0 => self.data[index].get_nimber_bounds().1,
+            _ => {
+                let largest = parts
+                    .indices
+                    .iter()
+                    .map(|index| self.data[*index].get_nimber_bounds().1)
+                    .fold(parts.modifier, usize::max);
+                usize::MAX.checked_shr(largest.leading_zeros()).unwrap_or(0)
+            }
+        }
+    }
+    /// the next unprocessed move of an entry, lazily generated moves are only taken once no other move is left
+    fn get_next_move_indices(&mut self, index: usize, iterators: &mut MoveIterators<G>) -> Option<Parts> {
+        if let Some(move_indices) = self.data[index].get_next_unprocessed_move_index() {
+            return Some(move_indices);
+        }
+        let taken = self.data[index].get_lazy_moves_taken()?;
+        let game = self.data[index].get_game();
+        let iterator = iterators.entry(index).or_insert_with(|| {
+            //a clone of the evaluator continues where the entry stopped taking moves
+            let mut iterator = game.unique_moves_iter().expect("the moves got generated lazily before");
+            iterator.by_ref().take(taken).for_each(drop);
+            iterator
+        });
+        match iterator.next() {
+            Some(_move) => {
+                self.data[index].take_lazy_move();
+                let move_indices = self.get_parts_of(_move);
+                //a move to the position itself or to a sum containing it would make the evaluation wait for itself
+                if move_indices.indices.contains(&index) {
+                    return self.get_next_move_indices(index, iterators);
+                }
+                Some(move_indices)
+            }
+            None => {
+                iterators.remove(&index);
+                self.data[index].finish_lazy_moves();
+                None
+            }
         }
     }
     /// generates a vec of all moves of the entry given by the index
     /// a move is represented as a vector of indices refering to the parts the position reached after the move
     /// for better performance all pairs of parts are removed
     /// because they cancel each other out in the calculation of the nimber
-    fn get_move_indices(&mut self, index: usize) {
+    fn get_move_indices(&mut self, index: usize, iterators: &mut MoveIterators<G>) {
         //if the moves are already generated stop generating
         if self.data[index].are_move_indices_generated() {
             return;
         }
+        if let Some(iterator) = self.data[index].get_game().unique_moves_iter() {
+            iterators.insert(index, iterator);
+            self.data[index].start_lazy_moves();
+            self.processing_entries += 1;
+            self.peak_processing_entries = self.peak_processing_entries.max(self.processing_entries);
+            return;
+        }
         let mut moves = self.data[index].get_unique_moves();
+        #[cfg(debug_assertions)]
+        self.check_moves_are_deterministic(index, &moves);
 
-        //sort by the biggest possible nimber
-        moves.sort_by(|a, b| a.get_max_nimber().cmp(&b.get_max_nimber()));
+        //the moves are taken from the front, the sorts are stable so ties keep the generated order
+        match &self.move_order.0 {
+            MoveOrder::LargestMaxNimberFirst => moves.sort_by_key(|a| Reverse(a.get_max_nimber())),
+            MoveOrder::SmallestMaxNimberFirst => moves.sort_by_key(|a| a.get_max_nimber()),
+            MoveOrder::Generated => {}
+            MoveOrder::Custom(key) => moves.sort_by_key(|a| key(a)),
+        }
 
-        let move_indices: Vec<Vec<usize>> = moves
+        let move_indices: Vec<Parts> = moves
             .into_iter()
-            .map(|_move| self.get_part_indices(_move))
-            .map(|part_indices| remove_pairs(part_indices))
+            .map(|_move| self.get_parts_of(_move))
             .collect();
+        self.set_move_indices(index, move_indices);
+    }
+    /// sets the moves of an entry which has no moves yet and bounds its nimber by them
+    fn set_move_indices(&mut self, index: usize, mut move_indices: Vec<Parts>) {
+        //a move to the position itself would make the evaluation wait for itself, and so would a move to a sum
+        //containing the position, both can only exist in loopy games, where the nimber is not defined anyway
+        move_indices.retain(|parts| !parts.indices.contains(&index));
+        //a position without moves is done right away, while a move to the empty sum has no parts but still counts
+        if move_indices.is_empty() {
+            self.data[index].set_nimber(0);
+            self.entry_done(index);
+            return;
+        }
+        //the mex of the nimbers of the moves can't exceed the number of moves
+        let move_count = move_indices.len();
+        //nor the value right above the largest nimber any move can have
+        let max_move_nimber = move_indices.iter().map(|parts| self.get_max_nimber_of_parts(parts)).max();
+        let max_nimber = max_move_nimber.map_or(0, |max| max.saturating_add(1)).min(move_count);
 
         self.data[index].set_child_indices(move_indices);
+        self.processing_entries += 1;
+        self.peak_processing_entries = self.peak_processing_entries.max(self.processing_entries);
+        self.data[index].limit_nimbers(max_nimber);
+        if self.data[index].get_nimber().is_some() {
+            self.entry_done(index);
+        }
+    }
+    /// panics if the moves differ from the moves generated for an equal game before,
+    /// e.g. after an entry got evicted, since the cache relies on equal games having equal moves
+    #[cfg(debug_assertions)]
+    fn check_moves_are_deterministic(&mut self, index: usize, moves: &[G]) {
+        let mut move_hashes: Vec<u64> = moves.iter().map(hash_of).collect();
+        move_hashes.sort_unstable();
+        let moves_hash = hash_of(&move_hashes);
+        let game = self.data[index].get_shared_game().clone();
+        let previous_moves_hash = *self.move_hashes.0.entry(game).or_insert(moves_hash);
+        assert_eq!(
+            previous_moves_hash, moves_hash,
+            "get_unique_moves returned different moves for equal games"
+        );
+    }
+    /// splits a game into its parts, nim heaps are xored into the modifier instead of getting an index
+    /// and decomposable parts are replaced by their components
+    /// pairs of equal parts are removed since they cancel each other out
+    /// the progress of an entry which is not done, for another evaluator sharing the cache to take over
+    fn get_progress(&self, index: usize) -> Progress<G> {
+        let entry = &self.data[index];
+        let moves = entry.are_move_indices_generated().then(|| {
+            entry
+                .get_unprocessed_move_indices()
+                .map(|parts| {
+                    let mut modifier = parts.modifier;
+                    let games = parts
+                        .indices
+                        .iter()
+                        .map(|part| &self.data[*part])
+                        .filter_map(|part| {
+                            //only the nimber of an evicted part is left
+                            if part.is_evicted() {
+                                modifier ^= part.get_nimber().unwrap();
+                                return None;
+                            }
+                            Some(part.get_shared_game().clone())
+                        })
+                        .collect();
+                    (games, modifier)
+                })
+                .collect()
+        });
+        Progress {
+            possible_nimbers: entry.get_possible_nimbers().to_vec(),
+            ruled_out: entry.get_ruled_out().to_vec(),
+            moves,
+            lazy_moves_taken: entry.get_lazy_moves_taken(),
+        }
+    }
+    /// continues from the progress another evaluator made on the game of an entry
+    fn restore_progress(&mut self, index: usize, progress: Progress<G>, iterators: &mut MoveIterators<G>) {
+        if let Some(moves) = progress.moves {
+            let move_indices = moves
+                .into_iter()
+                .map(|(games, modifier)| Parts {
+                    indices: games.iter().map(|game| self.get_index_of(game)).collect(),
+                    modifier,
+                })
+                .collect();
+            if !self.data[index].are_move_indices_generated() {
+                self.processing_entries += 1;
+                self.peak_processing_entries = self.peak_processing_entries.max(self.processing_entries);
+            }
+            self.data[index].restore_moves(move_indices, progress.lazy_moves_taken);
+            //the iterator of the entry might have taken a different number of moves
+            iterators.remove(&index);
+        }
+        self.data[index].restore_nimbers(progress.possible_nimbers, progress.ruled_out);
     }
+    /// the parts of a game a query starts from, the slots of evicted entries are reclaimed first,
+    /// since the query holds no indices yet
+    fn get_query_parts(&mut self, g: G) -> Parts {
+        self.reclaim_evicted_entries();
+        self.get_parts_of(g)
+    }
+    /// drops the evicted entries, so an evaluator with a capacity keeps at most that many entries between queries
+    /// moves refering to an evicted entry keep its nimber in their modifier instead
+    /// the remaining entries move to other indices, so this only runs before a query, while no indices are held
+    fn reclaim_evicted_entries(&mut self) {
+        if self.spill.as_ref().is_none_or(|spill| spill.spilled == 0) {
+            return;
+        }
+        let mut kept = 0;
+        let new_indices: Vec<Result<usize, usize>> = self
+            .data
+            .iter()
+            .map(|entry| {
+                if entry.is_evicted() {
+                    return Err(entry.get_nimber().unwrap());
+                }
+                kept += 1;
+                Ok(kept - 1)
+            })
+            .collect();
+        self.done_entries -= self.data.len() - kept;
+        self.data.retain(|entry| !entry.is_evicted());
+        for entry in &mut self.data {
+            entry.remap_moves(&new_indices);
+        }
+        let remap = |index: &mut usize| match new_indices[*index] {
+            Ok(new_index) => {
+                *index = new_index;
+                true
+            }
+            Err(_) => false,
+        };
+        for index in self.index_map.values_mut() {
+            assert!(remap(index), "evicted entries have no key");
+        }
+        //orphans are never evicted, their replacements would lose their key
+        self.orphans = self.orphans.iter().filter_map(|index| new_indices[*index].ok()).collect();
+        self.done_log.0.retain_mut(|(_, index)| remap(index));
+        let spill = self.spill.as_mut().unwrap();
+        spill.done_indices.retain_mut(|index| remap(index));
+        spill.referenced = spill.referenced.iter().filter_map(|index| new_indices[*index].ok()).collect();
+        spill.spilled = 0;
+    }
+    fn get_parts_of(&mut self, g: G) -> Parts {
+        let mut parts = Parts::default();
+        self.add_parts(g.get_parts(), &mut parts);
+        parts.indices = remove_pairs(parts.indices);
+        parts
+    }
+    /// adds the given games to the sum, every game is decomposed as far as possible
+    fn add_parts(&mut self, games: Vec<G>, parts: &mut Parts) {
+        for game in games {
+            if let Some(nimber) = game.as_nim_heap() {
+                parts.modifier ^= nimber;
+            } else if let Some(components) = game.decompose() {
+                self.add_parts(components, parts);
+            } else {
+                let index = self.get_index_of_owned(game);
+                parts.indices.push(index);
+            }
+        }
+    }
+    /// gets the indices of all parts of a game, every part gets an entry
     pub fn get_part_indices(&mut self, g: G) -> Vec<usize> {
         g.get_parts()
             .iter()
             .map(|part| self.get_index_of(part))
             .collect()
     }
+    /// the index of the entry of the canonical form of a game, the entry is added if it is missing
+    /// an evaluator with a capacity moves its entries when a query reclaims the evicted ones, so the index is only valid until then
     pub fn get_index_of(&mut self, g: &G) -> usize {
-        if let Some(index) = self.index_map.get(g) {
-            return *index;
-        } else {
-            return self.add_game(g.clone());
+        self.get_index_of_owned(g.clone())
+    }
+    /// like `get_index_of`, but a new game is stored without cloning it
+    fn get_index_of_owned(&mut self, g: G) -> usize {
+        let g = g.canonical();
+        self.lookup(&g).unwrap_or_else(|| self.insert_game(g))
+    }
+    /// the index of a game which is in memory
+    fn lookup(&mut self, g: &G) -> Option<usize> {
+        let index = *self.index_map.get(g)?;
+        self.mark_used(index);
+        Some(index)
+    }
+    /// adds a game which is missing in memory
+    fn insert_game(&mut self, g: G) -> usize {
+        let index = self.add_game(g);
+        if self.data[index].get_nimber().is_some() {
+            return index;
+        }
+        //a game missing in memory might have been spilled earlier
+        let game = self.data[index].get_game();
+        match self.spill.as_ref().map(|spill| spill.load(game)) {
+            Some(Ok(Some(nimber))) => {
+                self.data[index].set_nimber(nimber);
+                self.entry_done(index);
+            }
+            Some(Err(error)) => self.spill_failed(error),
+            _ => (),
         }
+        index
     }
     pub fn add_game(&mut self, game: G) -> usize {
-        let entry = Entry::new(game.clone());
+        let game = Arc::new(game);
+        let mut entry = Entry::new(game.clone());
+        if let Some(nimber) = game.as_nim_heap() {
+            entry.set_nimber(nimber);
+        } else if game.is_terminal() {
+            entry.set_nimber(0);
+        } else if let Some(move_count) = game.move_count() {
+            entry.limit_nimbers(move_count);
+        }
         let index = self.data.len();
         self.index_map.insert(game, index);
         self.data.push(entry);
-        return index;
+        if self.data[index].get_nimber().is_some() {
+            self.entry_done(index);
+        }
+        index
+    }
+    /// indexes the games of the entries again, sharing every game between its entry and its key
+    /// evicted entries and orphans have no key
+    pub(crate) fn rebuild_index_map(&mut self) {
+        self.index_map = self
+            .data
+            .iter()
+            .enumerate()
+            .filter(|(index, entry)| !entry.is_evicted() && !self.orphans.contains(index))
+            .map(|(index, entry)| (entry.get_shared_game().clone(), index))
+            .collect();
+    }
+    /// copies all known nimbers of an other evaluator into this evaluator, e.g. to combine evaluations done elsewhere
+    /// entries of the other evaluator which are still processing are ignored
+    /// panics if both evaluators know different nimbers for the same game, since one of them has to be wrong
+    pub fn merge(&mut self, other: &Evaluator<G>) {
+        for (game, other_index) in &other.index_map {
+            if let Some(nimber) = other.data[*other_index].get_nimber() {
+                self.merge_nimber(game, nimber);
+            }
+        }
+    }
+    /// copies the nimbers and the progress of the evaluators which shared the cache into this evaluator
+    /// the iterators of entries which get a different progress are dropped
+    fn merge_shared_cache(&mut self, cache: SharedCache<G>, iterators: &mut MoveIterators<G>) {
+        for (game, result) in cache.into_results() {
+            match result {
+                Ok(nimber) => self.merge_nimber(&game, nimber),
+                Err(progress) => {
+                    let index = self.get_index_of(&game);
+                    if self.data[index].get_nimber().is_none() {
+                        self.restore_progress(index, progress, iterators);
+                    }
+                }
+            }
+        }
+    }
+    /// sets the nimber of a game which got calculated elsewhere
+    fn merge_nimber(&mut self, game: &G, nimber: usize) {
+        let index = self.get_index_of(game);
+        match self.data[index].get_nimber() {
+            Some(known) => assert_eq!(known, nimber, "conflicting nimbers for {}", game.label()),
+            None => {
+                self.data[index].set_nimber(nimber);
+                self.entry_done(index);
+            }
+        }
     }
 }
 
-fn remove_pairs<T>(mut vec: Vec<T>) -> Vec<T>
+//only the threaded evaluation requires the games to be shared between threads
+impl<G> Evaluator<G>
 where
-    T: Eq + Ord,
+    G: Impartial<G> + Send + Sync,
 {
-    vec.sort();
-    let mut i = 0;
-    while i + 1 < vec.len() {
-        if vec[i] == vec[i + 1] {
-            vec.remove(i);
-            vec.remove(i);
-        } else {
-            i += 1;
+    /// calculates the nimbers of many games in parallel on the global rayon thread pool
+    /// every thread works on its own copy of the cache, but the positions the threads expand are shared,
+    /// so every position is expanded by a single thread, the others wait for it and take over its progress, see `SharedCache`
+    /// all copies are merged back into this evaluator afterwards
+    pub fn get_nimbers_parallel(&mut self, games: &[G]) -> Vec<usize> {
+        self.uncancellable(|evaluator| evaluator.try_get_nimbers_parallel(games))
+            .into_iter()
+            .map(|nimber| nimber.expect("uncancellable evaluations always finish"))
+            .collect()
+    }
+    /// like `get_nimbers_parallel` but the nimbers of games that could not be evaluated because of a cancellation are `None`
+    fn try_get_nimbers_parallel(&mut self, games: &[G]) -> Vec<Option<usize>> {
+        let halted = Arc::new(AtomicBool::new(false));
+        let (cache, nimbers) = self.run_in_forks(games.len(), &halted, |fork, i| fork.try_get_nimber(games[i].clone()).ok());
+        self.merge_shared_cache(cache, &mut MoveIterators::default());
+        nimbers.into_iter().map(Option::flatten).collect()
+    }
+    /// evaluates the sums side by side on the threads of the current pool, each up to its bound, see `ParallelEvaluation`
+    /// the forks are merged back by the caller
+    fn get_bounded_nimbers_in_forks(&self, sums: &[(Parts, usize)], halt_at: Option<usize>) -> (SharedCache<G>, Vec<Option<usize>>) {
+        //the state of the rule out all forks share, once one of them reached the candidate the others stop
+        let halted = Arc::new(AtomicBool::new(false));
+        let (cache, nimbers) = self.run_in_forks(sums.len(), &halted, |fork, i| {
+            let (parts, bound) = &sums[i];
+            let nimber = fork.get_bounded_nimber_by_parts(parts, *bound, &mut MoveIterators::default());
+            if nimber.is_some() && nimber == halt_at {
+                halted.store(true, Ordering::Relaxed);
+            }
+            nimber
+        });
+        (cache, nimbers.into_iter().map(Option::flatten).collect())
+    }
+    /// runs the jobs on one fork of the evaluator for every thread of the current pool
+    /// the forks share the positions they expand, so every position is expanded by a single fork,
+    /// the others wait for it and take over its progress instead, see `SharedCache`
+    /// the shared cache is returned to be merged back, the results of jobs which were not started
+    /// because the evaluation got cancelled or halted are `None`
+    fn run_in_forks<T: Send>(
+        &self,
+        jobs: usize,
+        halted: &Arc<AtomicBool>,
+        job: impl Fn(&mut Evaluator<G>, usize) -> T + Sync,
+    ) -> (SharedCache<G>, Vec<Option<T>>) {
+        let cache = Arc::new(SharedCache::new());
+        let next_job = AtomicUsize::new(0);
+        let base: &Evaluator<G> = self;
+        let forks = rayon::current_num_threads().min(jobs);
+        let results: Vec<Vec<(usize, Option<T>)>> = (0..forks)
+            .into_par_iter()
+            .map(|id| {
+                let mut fork = base.clone();
+                fork.shared.0 = Some(Worker {
+                    cache: cache.clone(),
+                    id,
+                    halted: halted.clone(),
+                });
+                fork.parallel_evaluation.0 = None;
+                //the nimbers of the fork are reported once they are merged back
+                fork.on_done.0 = None;
+                //a fork only holds part of the results, so it is not worth a checkpoint
+                fork.checkpoint.0 = None;
+                //every fork takes the next job once it is done with its last one, so no fork idles while jobs are left
+                std::iter::from_fn(|| Some(next_job.fetch_add(1, Ordering::Relaxed)).filter(|i| *i < jobs))
+                    .map(|i| (i, (!fork.is_cancelled()).then(|| job(&mut fork, i))))
+                    .collect()
+            })
+            .collect();
+        //the forks are gone, so this is the last reference
+        let cache = Arc::into_inner(cache).expect("the forks dropped their references");
+        let mut ordered: Vec<Option<T>> = (0..jobs).map(|_| None).collect();
+        for (i, result) in results.into_iter().flatten() {
+            ordered[i] = result;
         }
+        (cache, ordered)
+    }
+    /// calculates the nimbers of many games in parallel on the global rayon thread pool
+    /// all threads share the positions they expand, so the subgames the games have in common are computed only once
+    /// the nimbers of games that could not be evaluated because of a cancellation are `None`
+    pub fn get_nimbers_batch(&mut self, games: &[G]) -> Vec<Option<usize>> {
+        self.try_get_nimbers_parallel(games)
+    }
+    /// creates an evaluator which rules out the nimbers of every queried game by evaluating its moves on `thread_count` threads,
+    /// every move is bounded by the candidate nimber and the threads stop once a move reaches it,
+    /// games with several unknown parts get their parts evaluated on the threads instead
+    /// the threads share the positions they expand, but a thread waits for a position another thread is expanding,
+    /// so this only pays off if the moves of the queried games don't share most of their subgames
+    pub fn with_thread_count(thread_count: usize) -> Evaluator<G> {
+        EvaluatorBuilder::new().threads(thread_count).build()
+    }
+    /// like `with_thread_count` but all the parallel work is done on the given thread pool
+    pub fn with_thread_pool(pool: Arc<ThreadPool>) -> Evaluator<G> {
+        EvaluatorBuilder::new().thread_pool(pool).build()
+    }
+    /// like `get_nimbers_parallel` but all the work is done on the given thread pool
+    pub fn get_nimbers_in_pool(&mut self, games: &[G], pool: &ThreadPool) -> Vec<usize> {
+        pool.install(|| self.get_nimbers_parallel(games))
+    }
+    /// like `get_bounded_nimbers_in_forks` but all the work is done on the given thread pool, for the threads of the evaluator
+    pub(crate) fn get_bounded_nimbers_in_pool(
+        &mut self,
+        sums: &[(Parts, usize)],
+        halt_at: Option<usize>,
+        iterators: &mut MoveIterators<G>,
+        pool: &ThreadPool,
+    ) -> Vec<Option<usize>> {
+        let (cache, nimbers) = pool.install(|| self.get_bounded_nimbers_in_forks(sums, halt_at));
+        self.merge_shared_cache(cache, iterators);
+        nimbers
+    }
+}
+
+impl<G> Evaluator<G>
+where
+    G: Impartial<G> + Serialize,
+{
+    /// creates an evaluator that keeps at most `limit` entries in memory,
+    /// the nimbers of evicted entries are spilled to the file at `path`
+    /// and transparently reloaded when they are needed again
+    pub fn with_spill(path: &Path, limit: usize) -> io::Result<Evaluator<G>> {
+        Ok(EvaluatorBuilder::new().spill(path, limit)?.build())
     }
-    return vec;
+}
+
+fn default_poll_interval() -> usize {
+    1024
+}
+
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// removes every pair of equal parts from a sum of games, since such a pair has the nimber 0
+/// of every part occurring an odd number of times one copy is kept, at the position of its first occurrence
+/// the nimber of the sum stays the same, so moves can be shrunk like this before they reach the evaluator
+pub fn cancel_symmetric_parts<T: Eq + Hash>(parts: &mut Vec<T>) {
+    let mut first_occurrences: HashMap<&T, usize> = HashMap::new();
+    let mut counts = vec![0; parts.len()];
+    for (i, part) in parts.iter().enumerate() {
+        counts[*first_occurrences.entry(part).or_insert(i)] += 1;
+    }
+    let mut keep = counts.into_iter().map(|count| count % 2 == 1);
+    parts.retain(|_| keep.next().unwrap());
+}
+
+/// cancels the pairs of equal indices and sorts the remaining ones
+fn remove_pairs(mut indices: Vec<usize>) -> Vec<usize> {
+    cancel_symmetric_parts(&mut indices);
+    indices.sort_unstable();
+    indices
 }
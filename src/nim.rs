@@ -0,0 +1,54 @@
+use crate::Impartial;
+use serde::{Deserialize, Serialize};
+
+/// nim, a move reduces any single heap to any smaller size
+/// the heaps are kept sorted and without empty heaps, so equal positions compare equal
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Nim {
+    heaps: Vec<usize>,
+}
+
+impl Nim {
+    pub fn new(mut heaps: Vec<usize>) -> Nim {
+        heaps.retain(|heap| *heap > 0);
+        heaps.sort_unstable();
+        Nim { heaps }
+    }
+}
+
+impl Impartial<Nim> for Nim {
+    /// every heap is an independent part
+    fn get_parts(self) -> Vec<Nim> {
+        self.heaps.iter().map(|heap| Nim::new(vec![*heap])).collect()
+    }
+    /// the nimber of nim is exactly the xor of its heaps
+    fn get_max_nimber(&self) -> usize {
+        self.heaps.iter().fold(0, |nimber, heap| nimber ^ heap)
+    }
+    fn get_unique_moves(&self) -> Vec<Nim> {
+        let mut moves = vec![];
+        for (i, heap) in self.heaps.iter().enumerate() {
+            //equal heaps lead to the same moves
+            if i > 0 && self.heaps[i - 1] == *heap {
+                continue;
+            }
+            for smaller in 0..*heap {
+                let mut heaps = self.heaps.clone();
+                heaps[i] = smaller;
+                moves.push(Nim::new(heaps));
+            }
+        }
+        moves
+    }
+    /// a single heap is a nim heap of its size and no heap at all the empty heap
+    fn as_nim_heap(&self) -> Option<usize> {
+        match self.heaps[..] {
+            [] => Some(0),
+            [heap] => Some(heap),
+            _ => None,
+        }
+    }
+    fn label(&self) -> String {
+        format!("{:?}", self.heaps)
+    }
+}
@@ -0,0 +1,151 @@
+use crate::Impartial;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// well known octal games by their names
+pub const NAMED_GAMES: [(&str, &str); 6] = [
+    ("kayles", "0.77"),
+    ("dawsons_chess", "0.137"),
+    ("dawsons_kayles", "0.07"),
+    ("guiles", "0.15"),
+    ("officers", "0.6"),
+    ("treblecross", "0.007"),
+];
+
+/// the reasons the notation of an octal game can't be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OctalCodeError {
+    /// the notation does not start with "0."
+    MissingPrefix,
+    /// only the digits 0 to 7 encode moves
+    InvalidDigit(char),
+    /// the name is neither one of the `NAMED_GAMES` nor a code
+    UnknownName,
+}
+
+impl fmt::Display for OctalCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OctalCodeError::MissingPrefix => write!(f, "an octal code has to start with \"0.\""),
+            OctalCodeError::InvalidDigit(digit) => write!(f, "{:?} is no octal digit", digit),
+            OctalCodeError::UnknownName => write!(f, "the name is neither a known octal game nor a code like \"0.137\""),
+        }
+    }
+}
+
+impl std::error::Error for OctalCodeError {}
+
+/// the empty position of an octal game given by one of the `NAMED_GAMES` or by its code like "0.137"
+pub fn named(name: &str) -> Result<OctalGame, OctalCodeError> {
+    let notation = match NAMED_GAMES.iter().find(|(game, _)| *game == name) {
+        Some((_, notation)) => notation,
+        None if name.starts_with("0.") => name,
+        None => return Err(OctalCodeError::UnknownName),
+    };
+    Ok(OctalGame::new(0, OctalGame::parse_code(notation)?))
+}
+
+/// an octal game, `code[k]` encodes whether `k` tokens may be removed from a heap
+/// if the bit 1 is set the heap may be removed completely,
+/// if the bit 2 is set one nonempty heap may be left and
+/// if the bit 4 is set the rest may be split into two nonempty heaps
+/// all positions of one game share the same code, so only the heaps are used for hashing and comparisons
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OctalGame {
+    pub heaps: Vec<usize>,
+    pub code: Arc<Vec<u8>>,
+}
+
+impl OctalGame {
+    pub fn new(heap: usize, code: Vec<u8>) -> OctalGame {
+        let empty = OctalGame {
+            heaps: vec![],
+            code: Arc::new(code),
+        };
+        empty.with_heaps(vec![heap])
+    }
+    /// parses the usual notation of an octal game like "0.137" into a code
+    pub fn parse_code(notation: &str) -> Result<Vec<u8>, OctalCodeError> {
+        let digits = notation.strip_prefix("0.").ok_or(OctalCodeError::MissingPrefix)?;
+        let mut code = vec![0];
+        for digit in digits.chars() {
+            let value = digit.to_digit(8).ok_or(OctalCodeError::InvalidDigit(digit))?;
+            code.push(value as u8);
+        }
+        Ok(code)
+    }
+    /// a position with other heaps of the same game, empty heaps are dropped
+    pub fn with_heaps(&self, mut heaps: Vec<usize>) -> OctalGame {
+        heaps.retain(|heap| *heap > 0);
+        heaps.sort_unstable();
+        OctalGame {
+            heaps,
+            code: self.code.clone(),
+        }
+    }
+}
+
+impl PartialEq for OctalGame {
+    fn eq(&self, other: &Self) -> bool {
+        self.heaps == other.heaps
+    }
+}
+
+impl Eq for OctalGame {}
+
+impl Hash for OctalGame {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.heaps.hash(state);
+    }
+}
+
+impl Impartial<OctalGame> for OctalGame {
+    fn get_parts(self) -> Vec<OctalGame> {
+        self.heaps.iter().map(|heap| self.with_heaps(vec![*heap])).collect()
+    }
+    /// every move removes at least one token, so the game can't last longer than the number of tokens
+    fn get_max_nimber(&self) -> usize {
+        self.heaps.iter().sum()
+    }
+    fn get_unique_moves(&self) -> Vec<OctalGame> {
+        let mut moves = vec![];
+        for (i, heap) in self.heaps.iter().enumerate() {
+            //equal heaps lead to the same moves
+            if i > 0 && self.heaps[i - 1] == *heap {
+                continue;
+            }
+            let mut others = self.heaps.clone();
+            others.remove(i);
+            for (k, digit) in self.code.iter().enumerate().skip(1).take(*heap) {
+                let rest = heap - k;
+                let mut leaves = vec![];
+                if digit & 1 != 0 && rest == 0 {
+                    leaves.push(vec![]);
+                }
+                if digit & 2 != 0 && rest > 0 {
+                    leaves.push(vec![rest]);
+                }
+                if digit & 4 != 0 {
+                    for smaller in 1..=rest / 2 {
+                        leaves.push(vec![smaller, rest - smaller]);
+                    }
+                }
+                for leave in leaves {
+                    let mut heaps = others.clone();
+                    heaps.extend(leave);
+                    moves.push(self.with_heaps(heaps));
+                }
+            }
+        }
+        moves
+    }
+    fn label(&self) -> String {
+        format!("{:?}", self.heaps)
+    }
+    /// the rules are shared by all positions, so only the heaps count
+    fn heap_size(&self) -> usize {
+        self.heaps.capacity() * std::mem::size_of::<usize>()
+    }
+}
@@ -0,0 +1,19 @@
+/// searches for the smallest preperiod `p` and period `q` with `nimbers[i] == nimbers[i + q]` for all `i >= p`
+/// a period only counts as confirmed if it repeated at least twice after the preperiod,
+/// i.e. if there are at least `p + 2 * q` nimbers, otherwise `None` is returned
+/// this is a heuristic, a short sequence can still show a period which breaks later on
+pub fn find_period(nimbers: &[usize]) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize)> = None;
+    for period in 1..=nimbers.len() / 2 {
+        //the smallest preperiod for this period is found by walking back from the end
+        let mut preperiod = nimbers.len() - period;
+        while preperiod > 0 && nimbers[preperiod - 1] == nimbers[preperiod - 1 + period] {
+            preperiod -= 1;
+        }
+        let is_confirmed = preperiod + 2 * period <= nimbers.len();
+        if is_confirmed && best.is_none_or(|(best_preperiod, _)| preperiod < best_preperiod) {
+            best = Some((preperiod, period));
+        }
+    }
+    best
+}
@@ -0,0 +1,84 @@
+use crate::{Evaluator, Impartial};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// the version of the file format, files of other versions are rejected
+const FORMAT_VERSION: u32 = 1;
+
+/// the reasons saving or loading an evaluator can fail
+#[derive(Debug)]
+pub enum PersistenceError {
+    Io(io::Error),
+    Format(bincode::Error),
+    VersionMismatch { found: u32, expected: u32 },
+}
+
+impl fmt::Display for PersistenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistenceError::Io(err) => write!(f, "io error: {}", err),
+            PersistenceError::Format(err) => write!(f, "invalid evaluator file: {}", err),
+            PersistenceError::VersionMismatch { found, expected } => write!(
+                f,
+                "the evaluator file has format version {} but version {} is expected",
+                found, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PersistenceError {}
+
+impl From<io::Error> for PersistenceError {
+    fn from(err: io::Error) -> Self {
+        PersistenceError::Io(err)
+    }
+}
+
+impl From<bincode::Error> for PersistenceError {
+    fn from(err: bincode::Error) -> Self {
+        PersistenceError::Format(err)
+    }
+}
+
+impl<G> Evaluator<G>
+where
+    G: Impartial<G> + Serialize,
+{
+    /// saves the whole cache including the partially processed entries,
+    /// so an evaluation cancelled before saving continues exactly where it stopped after loading
+    /// callbacks, the cancel flag and the spill are not saved
+    pub fn save_to_path(&self, path: &Path) -> Result<(), PersistenceError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl<G> Evaluator<G>
+where
+    G: Impartial<G> + DeserializeOwned,
+{
+    /// loads an evaluator saved with `save_to_path`
+    pub fn load_from_path(path: &Path) -> Result<Evaluator<G>, PersistenceError> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut version = [0; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != FORMAT_VERSION {
+            return Err(PersistenceError::VersionMismatch {
+                found: version,
+                expected: FORMAT_VERSION,
+            });
+        }
+        let mut evaluator: Evaluator<G> = bincode::deserialize_from(reader)?;
+        evaluator.rebuild_index_map();
+        Ok(evaluator)
+    }
+}
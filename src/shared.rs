@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// how long a claim is waited for before checking for a cancellation again
+const WAIT_SLICE: Duration = Duration::from_millis(5);
+
+/// the progress of a position which is not done yet, in a form every evaluator can take over
+pub(super) struct Progress<G> {
+    pub possible_nimbers: Vec<usize>,
+    pub ruled_out: Vec<usize>,
+    /// the unprocessed moves given by the games of their parts and the xor of the nimbers known without an entry,
+    /// `None` if the moves are not generated yet
+    pub moves: Option<Vec<(Vec<Arc<G>>, usize)>>,
+    pub lazy_moves_taken: Option<usize>,
+}
+
+/// the state of a position in the shared cache
+enum Slot<G> {
+    /// an evaluator is expanding the position right now
+    Claimed,
+    /// the last evaluator which expanded the position left this progress behind
+    Released { progress: Progress<G>, by: usize },
+    Done(usize),
+}
+
+/// the positions which evaluators working side by side on different threads expand,
+/// every position is expanded by at most one of them at a time,
+/// the others wait for it and continue from the progress it leaves behind, so no position is expanded twice
+pub(super) struct SharedCache<G> {
+    slots: Mutex<HashMap<Arc<G>, Slot<G>>>,
+    released: Condvar,
+}
+
+/// an evaluator expanding the positions of a shared cache together with others
+pub(super) struct Worker<G> {
+    pub cache: Arc<SharedCache<G>>,
+    /// tells apart the evaluators sharing the cache
+    pub id: usize,
+    /// set once the result the evaluators work towards is decided, e.g. once one of them found a move
+    /// reaching the candidate of a rule out, the evaluators stop then
+    pub halted: Arc<AtomicBool>,
+}
+
+impl<G> Worker<G> {
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::Relaxed)
+    }
+}
+
+impl<G> Clone for Worker<G> {
+    fn clone(&self) -> Self {
+        Worker {
+            cache: self.cache.clone(),
+            id: self.id,
+            halted: self.halted.clone(),
+        }
+    }
+}
+
+/// the outcome of claiming a position
+pub(super) enum Claimed<G>
+where
+    G: Hash + Eq,
+{
+    /// the position is claimed, together with the progress of another evaluator which has to be taken over
+    Claim(Claim<G>, Option<Progress<G>>),
+    Done(usize),
+    /// the evaluation got cancelled while waiting for another evaluator
+    Cancelled,
+}
+
+/// the exclusive right to expand a position, it is released when dropped,
+/// a claim dropped without leaving progress behind, e.g. by a panic, lets the next evaluator start over
+pub(super) struct Claim<G>
+where
+    G: Hash + Eq,
+{
+    cache: Arc<SharedCache<G>>,
+    game: Arc<G>,
+    by: usize,
+    progress: Option<Progress<G>>,
+}
+
+impl<G> SharedCache<G>
+where
+    G: Hash + Eq,
+{
+    pub fn new() -> SharedCache<G> {
+        SharedCache {
+            slots: Mutex::new(HashMap::new()),
+            released: Condvar::new(),
+        }
+    }
+    fn lock(&self) -> MutexGuard<'_, HashMap<Arc<G>, Slot<G>>> {
+        //a panicking evaluator leaves every slot consistent, its claims are removed while unwinding
+        self.slots.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    /// claims a position for the worker `by`, waits while another worker holds the claim
+    /// the waiting stops once `is_cancelled` returns true
+    pub fn claim(self: &Arc<Self>, game: &Arc<G>, by: usize, is_cancelled: impl Fn() -> bool) -> Claimed<G> {
+        let mut slots = self.lock();
+        loop {
+            match slots.get(game) {
+                Some(Slot::Claimed) => {
+                    if is_cancelled() {
+                        return Claimed::Cancelled;
+                    }
+                    slots = self
+                        .released
+                        .wait_timeout(slots, WAIT_SLICE)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0;
+                }
+                Some(Slot::Done(nimber)) => return Claimed::Done(*nimber),
+                _ => break,
+            }
+        }
+        let progress = match slots.insert(game.clone(), Slot::Claimed) {
+            //the worker which left the progress behind still has it
+            Some(Slot::Released { progress, by: last }) if last != by => Some(progress),
+            _ => None,
+        };
+        let claim = Claim {
+            cache: self.clone(),
+            game: game.clone(),
+            by,
+            progress: None,
+        };
+        Claimed::Claim(claim, progress)
+    }
+    /// stores the nimber of a position, a claim on it is released
+    pub fn publish(&self, game: &Arc<G>, nimber: usize) {
+        let mut slots = self.lock();
+        if let Some(Slot::Claimed) = slots.insert(game.clone(), Slot::Done(nimber)) {
+            self.released.notify_all();
+        }
+    }
+    /// the nimbers and the progress of all positions, once no worker is left
+    pub fn into_results(self) -> impl Iterator<Item = (Arc<G>, Result<usize, Progress<G>>)> {
+        self.slots
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .into_iter()
+            .filter_map(|(game, slot)| match slot {
+                Slot::Done(nimber) => Some((game, Ok(nimber))),
+                Slot::Released { progress, .. } => Some((game, Err(progress))),
+                Slot::Claimed => None,
+            })
+    }
+}
+
+impl<G> Claim<G>
+where
+    G: Hash + Eq,
+{
+    /// releases the claim of a position which is not done, leaving its progress to the next worker
+    pub fn release(mut self, progress: Progress<G>) {
+        self.progress = Some(progress);
+    }
+}
+
+impl<G> Drop for Claim<G>
+where
+    G: Hash + Eq,
+{
+    fn drop(&mut self) {
+        let mut slots = self.cache.lock();
+        //a published nimber stays
+        if let Some(Slot::Claimed) = slots.get(&self.game) {
+            match self.progress.take() {
+                Some(progress) => slots.insert(self.game.clone(), Slot::Released { progress, by: self.by }),
+                None => slots.remove(&self.game),
+            };
+            self.cache.released.notify_all();
+        }
+    }
+}
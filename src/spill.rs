@@ -0,0 +1,217 @@
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// a store for the nimbers of games that got evicted from memory
+pub(super) trait SpillBackend<G>: Send {
+    fn store(&mut self, game: &G, nimber: usize) -> io::Result<()>;
+    fn load(&mut self, game: &G) -> io::Result<Option<usize>>;
+    /// forgets the nimber of a game, returns whether it was stored
+    fn remove(&mut self, game: &G) -> io::Result<bool>;
+    /// forgets all stored nimbers
+    fn clear(&mut self) -> io::Result<()>;
+}
+
+/// keeps the spilled nimbers in a single append only file,
+/// only the offsets of the records are kept in memory
+/// a record consists of the length of the serialized game, the serialized game and the nimber
+pub(super) struct DiskSpill {
+    file: File,
+    offsets: HashMap<u64, Vec<u64>>,
+}
+
+impl DiskSpill {
+    pub fn create(path: &Path) -> io::Result<DiskSpill> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(DiskSpill {
+            file,
+            offsets: HashMap::new(),
+        })
+    }
+    fn read_u64(&mut self) -> io::Result<u64> {
+        let mut bytes = [0; 8];
+        self.file.read_exact(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+    /// finds the offset of the record of a key
+    /// the file is left right behind the stored key
+    fn find(&mut self, key: &[u8]) -> io::Result<Option<u64>> {
+        let Some(offsets) = self.offsets.get(&hash_key(key)).cloned() else {
+            return Ok(None);
+        };
+        //different games can share a hash, so the stored keys have to be compared
+        for offset in offsets {
+            self.file.seek(SeekFrom::Start(offset))?;
+            if self.read_u64()? != key.len() as u64 {
+                continue;
+            }
+            let mut stored_key = vec![0; key.len()];
+            self.file.read_exact(&mut stored_key)?;
+            if stored_key == key {
+                return Ok(Some(offset));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<G> SpillBackend<G> for DiskSpill
+where
+    G: Serialize,
+{
+    fn store(&mut self, game: &G, nimber: usize) -> io::Result<()> {
+        let key = serialize_key(game)?;
+        let offset = self.file.seek(SeekFrom::End(0))?;
+        self.file.write_all(&(key.len() as u64).to_le_bytes())?;
+        self.file.write_all(&key)?;
+        self.file.write_all(&(nimber as u64).to_le_bytes())?;
+        self.offsets.entry(hash_key(&key)).or_default().push(offset);
+        Ok(())
+    }
+    fn load(&mut self, game: &G) -> io::Result<Option<usize>> {
+        let key = serialize_key(game)?;
+        match self.find(&key)? {
+            Some(_) => Ok(Some(self.read_u64()? as usize)),
+            None => Ok(None),
+        }
+    }
+    /// only the offset is forgotten, the record stays in the file
+    fn remove(&mut self, game: &G) -> io::Result<bool> {
+        let key = serialize_key(game)?;
+        let Some(offset) = self.find(&key)? else {
+            return Ok(false);
+        };
+        let hash = hash_key(&key);
+        let offsets = self.offsets.get_mut(&hash).unwrap();
+        offsets.retain(|other| *other != offset);
+        if offsets.is_empty() {
+            self.offsets.remove(&hash);
+        }
+        Ok(true)
+    }
+    fn clear(&mut self) -> io::Result<()> {
+        self.file.set_len(0)?;
+        self.offsets.clear();
+        Ok(())
+    }
+}
+
+/// forgets the nimbers of evicted games, they get recalculated when they are needed again
+pub(super) struct Discard;
+
+impl<G> SpillBackend<G> for Discard {
+    fn store(&mut self, _: &G, _: usize) -> io::Result<()> {
+        Ok(())
+    }
+    fn load(&mut self, _: &G) -> io::Result<Option<usize>> {
+        Ok(None)
+    }
+    fn remove(&mut self, _: &G) -> io::Result<bool> {
+        Ok(false)
+    }
+    fn clear(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn serialize_key<G: Serialize>(game: &G) -> io::Result<Vec<u8>> {
+    bincode::serialize(game).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn hash_key(key: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// the spill configuration and bookkeeping of an evaluator
+/// clones of an evaluator share the same backend
+#[derive(Clone)]
+pub(super) struct Spill<G> {
+    backend: Arc<Mutex<dyn SpillBackend<G>>>,
+    /// the maximal number of entries kept in memory
+    pub limit: usize,
+    /// indices of done entries which are still in memory, oldest first
+    pub done_indices: VecDeque<usize>,
+    /// done entries which got used again since they were queued
+    pub referenced: HashSet<usize>,
+    /// the number of entries which got evicted so far
+    pub spilled: usize,
+}
+
+impl<G> Spill<G> {
+    pub fn new(backend: impl SpillBackend<G> + 'static, limit: usize) -> Spill<G> {
+        Spill::with_backend(Arc::new(Mutex::new(backend)), limit)
+    }
+    pub fn with_backend(backend: Arc<Mutex<dyn SpillBackend<G>>>, limit: usize) -> Spill<G> {
+        Spill {
+            backend,
+            limit,
+            done_indices: VecDeque::new(),
+            referenced: HashSet::new(),
+            spilled: 0,
+        }
+    }
+    pub fn store(&self, game: &G, nimber: usize) -> io::Result<()> {
+        self.backend.lock().unwrap().store(game, nimber)
+    }
+    pub fn load(&self, game: &G) -> io::Result<Option<usize>> {
+        self.backend.lock().unwrap().load(game)
+    }
+    pub fn remove(&self, game: &G) -> io::Result<bool> {
+        self.backend.lock().unwrap().remove(game)
+    }
+    /// forgets everything, also the nimbers stored in the backend
+    /// a backend shared with clones keeps their nimbers, this spill discards the nimbers from then on instead
+    pub fn clear(&mut self) -> io::Result<()> {
+        let cleared = if Arc::strong_count(&self.backend) == 1 {
+            self.backend.lock().unwrap().clear()
+        } else {
+            self.detach();
+            Ok(())
+        };
+        self.done_indices.clear();
+        self.referenced.clear();
+        self.spilled = 0;
+        cleared
+    }
+    /// stops using the backend, the nimbers evicted from then on are discarded
+    /// used once the backend failed, since its content can't be trusted anymore
+    pub fn detach(&mut self) {
+        self.backend = Arc::new(Mutex::new(Discard));
+    }
+}
+
+impl<G> fmt::Debug for Spill<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Spill")
+            .field("limit", &self.limit)
+            .field("done_indices", &self.done_indices)
+            .field("referenced", &self.referenced)
+            .field("spilled", &self.spilled)
+            .finish()
+    }
+}
+
+impl<G> PartialEq for Spill<G> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.backend, &other.backend)
+            && self.limit == other.limit
+            && self.done_indices == other.done_indices
+            && self.referenced == other.referenced
+            && self.spilled == other.spilled
+    }
+}
+
+impl<G> Eq for Spill<G> {}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// the number of entries in each state of the evaluation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CacheStats {
+    /// entries whose moves are not generated yet
+    pub stubs: usize,
+    /// entries whose moves are generated but whose nimber is not known yet
+    pub processing: usize,
+    /// entries whose nimber is known
+    pub done: usize,
+}
+
+impl CacheStats {
+    /// the number of entries in the cache
+    pub fn total(&self) -> usize {
+        self.stubs + self.processing + self.done
+    }
+}
+
+impl fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "stubs: {}, processing: {}, done: {}", self.stubs, self.processing, self.done)
+    }
+}
+
+/// counters describing how much work the evaluations did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Metrics {
+    /// lookups of entries whose nimber was already known
+    pub cache_hits: usize,
+    /// lookups of entries whose nimber still had to be calculated
+    pub cache_misses: usize,
+    /// moves evaluated while trying to rule out nimbers
+    pub move_evaluations: usize,
+}
@@ -0,0 +1,63 @@
+use crate::Impartial;
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// a heap from which any amount in the subtraction set `allowed` can be removed
+/// all heaps of one game share the same subtraction set,
+/// so only the heap size is used for hashing and comparisons
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtractionGame {
+    pub heap: usize,
+    pub allowed: Arc<Vec<usize>>,
+}
+
+impl SubtractionGame {
+    pub fn new(heap: usize, allowed: Vec<usize>) -> SubtractionGame {
+        SubtractionGame {
+            heap,
+            allowed: Arc::new(allowed),
+        }
+    }
+    /// a heap of another size with the same subtraction set
+    pub fn with_heap(&self, heap: usize) -> SubtractionGame {
+        SubtractionGame {
+            heap,
+            allowed: self.allowed.clone(),
+        }
+    }
+}
+
+impl PartialEq for SubtractionGame {
+    fn eq(&self, other: &Self) -> bool {
+        self.heap == other.heap
+    }
+}
+
+impl Eq for SubtractionGame {}
+
+impl Hash for SubtractionGame {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.heap.hash(state);
+    }
+}
+
+impl Impartial<SubtractionGame> for SubtractionGame {
+    fn get_parts(self) -> Vec<SubtractionGame> {
+        vec![self]
+    }
+    /// the nimber can neither exceed the number of moves nor the number of moves left in the game
+    fn get_max_nimber(&self) -> usize {
+        self.heap.min(self.allowed.len())
+    }
+    fn get_unique_moves(&self) -> Vec<SubtractionGame> {
+        self.allowed
+            .iter()
+            .filter(|amount| **amount <= self.heap)
+            .map(|amount| self.with_heap(self.heap - amount))
+            .collect()
+    }
+    fn label(&self) -> String {
+        self.heap.to_string()
+    }
+}
@@ -0,0 +1,58 @@
+use crate::Impartial;
+use serde::{Deserialize, Serialize};
+
+/// the disjunctive sum of games, a move is a move in exactly one of the components
+/// its nimber is the xor of the nimbers of the components
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GameSum<G>(pub Vec<G>);
+
+impl<G> Impartial<GameSum<G>> for GameSum<G>
+where
+    G: Impartial<G>,
+{
+    /// every part of every component is evaluated on its own
+    fn get_parts(self) -> Vec<GameSum<G>> {
+        self.0
+            .into_iter()
+            .flat_map(|component| component.get_parts())
+            .map(|part| GameSum(vec![part]))
+            .collect()
+    }
+    /// the xor of the components has at most as many bits as the largest of their bounds
+    fn get_max_nimber(&self) -> usize {
+        match &self.0[..] {
+            [component] => component.get_max_nimber(),
+            components => {
+                let largest = components.iter().map(|component| component.get_max_nimber()).max().unwrap_or(0);
+                usize::MAX.checked_shr(largest.leading_zeros()).unwrap_or(0)
+            }
+        }
+    }
+    fn get_unique_moves(&self) -> Vec<GameSum<G>> {
+        let mut moves = vec![];
+        for (i, component) in self.0.iter().enumerate() {
+            //equal components lead to the same moves
+            if self.0[..i].contains(component) {
+                continue;
+            }
+            for _move in component.get_unique_moves() {
+                let mut components = self.0.clone();
+                components[i] = _move;
+                moves.push(GameSum(components));
+            }
+        }
+        moves
+    }
+    fn as_nim_heap(&self) -> Option<usize> {
+        self.0
+            .iter()
+            .try_fold(0, |nimber, component| Some(nimber ^ component.as_nim_heap()?))
+    }
+    fn is_terminal(&self) -> bool {
+        self.0.iter().all(|component| component.is_terminal())
+    }
+    fn label(&self) -> String {
+        let labels: Vec<String> = self.0.iter().map(|component| component.label()).collect();
+        labels.join(" + ")
+    }
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// the nimbers of a family of games indexed by a single number, starting at 0
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct NimberTable {
+    nimbers: Vec<usize>,
+}
+
+impl NimberTable {
+    /// the nimber of the game with the given index if it is part of the table
+    pub fn get(&self, index: usize) -> Option<usize> {
+        self.nimbers.get(index).copied()
+    }
+    pub fn len(&self) -> usize {
+        self.nimbers.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.nimbers.is_empty()
+    }
+    pub fn nimbers(&self) -> &[usize] {
+        &self.nimbers
+    }
+}
+
+impl From<Vec<usize>> for NimberTable {
+    fn from(nimbers: Vec<usize>) -> Self {
+        NimberTable { nimbers }
+    }
+}
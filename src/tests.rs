@@ -1,13 +1,28 @@
 #![cfg(test)]
 use std::vec;
 
-use crate::{Evaluator, Impartial};
+use crate::nim;
+use crate::octal::{self, OctalCodeError, OctalGame};
+use crate::spill::{Spill, SpillBackend};
+use crate::subtraction::SubtractionGame;
+use crate::sum::GameSum;
+use crate::wythoff::Wythoff;
+use crate::{
+    cancel_symmetric_parts, find_period, Bounded, CacheStats, DecompositionMismatch, EvalError, Evaluator, Impartial, Metrics, MissingPosition, MoveOrder, NimberProof, NimberResult, Outcome, PersistenceError, RuledOutNimbers,
+};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 struct Kayles {
     kayles: Vec<usize>,
 }
 
+#[allow(clippy::needless_return, clippy::manual_div_ceil)]
 impl Impartial<Kayles> for Kayles {
     fn get_parts(self) -> Vec<Kayles> {
         return self
@@ -47,7 +62,15 @@ impl Impartial<Kayles> for Kayles {
     }
 }
 
+const KAYLES_NIMBERS: [usize; 105] = [
+    0, 1, 2, 3, 1, 4, 3, 2, 1, 4, 2, 6, 4, 1, 2, 7, 1, 4, 3, 2, 1, 4, 6, 7, 4, 1, 2, 8, 5, 4,
+    7, 2, 1, 8, 6, 7, 4, 1, 2, 3, 1, 4, 7, 2, 1, 8, 2, 7, 4, 1, 2, 8, 1, 4, 7, 2, 1, 4, 2, 7,
+    4, 1, 2, 8, 1, 4, 7, 2, 1, 8, 6, 7, 4, 1, 2, 8, 1, 4, 7, 2, 1, 8, 2, 7, 4, 1, 2, 8, 1, 4,
+    7, 2, 1, 8, 2, 7, 4, 1, 2, 8, 1, 4, 7, 2, 1
+];
+
 #[test]
+#[allow(clippy::needless_range_loop)]
 fn test_aperiodic_kayles_nimbers() {
     let nimbers: Vec<usize> = vec![
         0, 1, 2, 3, 1, 4, 3, 2, 1, 4, 2, 6, 4, 1, 2, 7, 1, 4, 3, 2, 1, 4, 6, 7, 4, 1, 2, 8, 5, 4,
@@ -67,6 +90,14 @@ fn test_aperiodic_kayles_nimbers() {
     }
 }
 #[test]
+fn test_kayles_moves_include_removing_the_maximum_number_of_pins() {
+    let moves = Kayles { kayles: vec![2] }.get_unique_moves();
+    assert!(moves.contains(&Kayles { kayles: vec![] }));
+    let moves = Kayles { kayles: vec![5] }.get_unique_moves();
+    assert!(moves.contains(&Kayles { kayles: vec![3] }));
+    assert!(moves.contains(&Kayles { kayles: vec![2, 1] }));
+}
+#[test]
 fn test_bound_creation_for_last_part_calculations() {
     for bound in 0..10 {
         for modifier in 0..(5 * bound) {
@@ -79,3 +110,1772 @@ fn test_bound_creation_for_last_part_calculations() {
         }
     }
 }
+#[test]
+fn test_spilled_kayles_nimbers_match_in_memory_nimbers() {
+    let path = std::env::temp_dir().join(format!("evaluator_spill_test_{}", std::process::id()));
+    let kayles = octal::named("kayles").unwrap();
+    let mut in_memory: Evaluator<OctalGame> = Evaluator::new();
+    let mut spilled: Evaluator<OctalGame> = Evaluator::with_spill(&path, 16).unwrap();
+
+    for i in 0..70 {
+        assert_eq!(
+            in_memory.get_nimber(kayles.with_heaps(vec![i])),
+            spilled.get_nimber(kayles.with_heaps(vec![i]))
+        );
+    }
+    assert!(spilled.spill.as_ref().unwrap().spilled > 0);
+    std::fs::remove_file(&path).unwrap();
+}
+#[test]
+fn test_capped_kayles_nimbers_match_in_memory_nimbers() {
+    let mut in_memory: Evaluator<Kayles> = Evaluator::new();
+    let mut capped: Evaluator<Kayles> = Evaluator::with_capacity(60);
+
+    for i in 0..70 {
+        assert_eq!(
+            in_memory.get_nimber(Kayles { kayles: vec![i] }),
+            capped.get_nimber(Kayles { kayles: vec![i] })
+        );
+    }
+    let spill = capped.spill.as_ref().unwrap();
+    assert!(spill.spilled > 0);
+    assert!(capped.index_map.len() < in_memory.index_map.len());
+}
+#[test]
+fn test_batched_kayles_nimbers_match_single_nimbers() {
+    let games: Vec<Kayles> = (0..100).map(|i| Kayles { kayles: vec![i] }).collect();
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let expected: Vec<Option<usize>> = KAYLES_NIMBERS[..100].iter().map(|nimber| Some(*nimber)).collect();
+    assert_eq!(expected, eval.get_nimbers_batch(&games));
+
+    let mut stopped: Evaluator<Kayles> = Evaluator::new();
+    stopped.stop();
+    assert_eq!(vec![None; 100], stopped.get_nimbers_batch(&games));
+}
+#[test]
+fn test_batched_games_share_their_overlapping_positions() {
+    let counters = Arc::new(Counters::default());
+    let game = Counted::new(0, vec![1, 2, 3], counters.clone());
+    let games: Vec<Counted> = [100, 100, 120, 120].iter().map(|heap| game.with_heap(*heap)).collect();
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+    let mut eval: Evaluator<Counted> = Evaluator::new();
+    let nimbers = pool.install(|| eval.get_nimbers_batch(&games));
+    assert_eq!(vec![Some(0), Some(0), Some(0), Some(0)], nimbers);
+    //both threads reach the heaps up to 100, but every heap gets expanded once
+    assert_eq!((1..=120).collect::<Vec<usize>>(), counters.expanded_heaps());
+    //every nimber a fork found got merged back
+    let mut serial: Evaluator<Counted> = Evaluator::new();
+    let other_game = Counted::new(0, vec![1, 2, 3], Arc::new(Counters::default()));
+    serial.warm(games.iter().map(|game| other_game.with_heap(game.game.heap)));
+    assert_eq!(serial.get_cache_stats().done, eval.get_cache_stats().done);
+    eval.get_nimbers_parallel(&games);
+    assert_eq!(120, counters.move_generations());
+}
+#[test]
+fn test_parallel_kayles_nimbers_match_serial_nimbers() {
+    let games: Vec<Kayles> = (0..60).map(|i| Kayles { kayles: vec![i] }).collect();
+    let mut serial: Evaluator<Kayles> = Evaluator::new();
+    let serial_nimbers: Vec<usize> = games.iter().map(|game| serial.get_nimber(game.clone())).collect();
+
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+    let mut pooled: Evaluator<Kayles> = Evaluator::new();
+    assert_eq!(serial_nimbers, pooled.get_nimbers_in_pool(&games, &pool));
+
+    let mut parallel: Evaluator<Kayles> = Evaluator::new();
+    assert_eq!(serial_nimbers, parallel.get_nimbers_parallel(&games));
+
+    //the merged cache already knows every nimber
+    for (game, nimber) in games.iter().zip(serial_nimbers) {
+        let index = parallel.index_map[game];
+        assert_eq!(Some(nimber), parallel.data[index].get_nimber());
+    }
+}
+#[test]
+fn test_outcome_bitset_matches_outcomes() {
+    let make = |i| Kayles { kayles: vec![i] };
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let bits = eval.export_outcome_bitset(make, 0..80);
+
+    let mut loaded: Evaluator<Kayles> = Evaluator::new();
+    loaded.load_outcome_bitset(make, 0..80, &bits);
+
+    for i in 0..80 {
+        let outcome = eval.get_outcome(make(i));
+        assert_eq!(bits[i], outcome == Outcome::P);
+        assert_eq!(outcome, loaded.get_outcome(make(i)));
+        //the seeded outcomes are known without generating any moves
+        let index = loaded.index_map[&make(i)];
+        assert!(!loaded.data[index].are_move_indices_generated());
+    }
+}
+
+#[test]
+fn test_nim_heaps_need_no_cache_entries() {
+    let mut eval: Evaluator<nim::Nim> = Evaluator::new();
+    assert_eq!(3 ^ 5 ^ 7, eval.get_nimber(nim::Nim::new(vec![3, 5, 7])));
+    assert_eq!(0, eval.get_nimber(nim::Nim::new(vec![4, 4])));
+    assert_eq!(0, eval.data.len());
+}
+#[test]
+fn test_nim_nimber_is_the_xor_of_its_heaps() {
+    let mut eval: Evaluator<nim::Nim> = Evaluator::new();
+    assert_eq!(1, eval.get_nimber(nim::Nim::new(vec![3, 5, 7])));
+    assert_eq!(0, eval.get_nimber(nim::Nim::new(vec![6, 0, 6])));
+    assert_eq!(6 ^ 9, eval.get_nimber(nim::Nim::new(vec![6, 9])));
+}
+#[test]
+fn test_misere_nim_outcomes() {
+    let mut eval: Evaluator<nim::Nim> = Evaluator::new();
+    for a in 0..5 {
+        for b in 0..5 {
+            for c in 0..4 {
+                let heaps = vec![a, b, c];
+                //misère nim is lost iff all heaps are at most 1 and their xor is 1, or some heap is larger and their xor is 0
+                let xor = a ^ b ^ c;
+                let small = heaps.iter().all(|heap| *heap <= 1);
+                let expected = if (small && xor == 1) || (!small && xor == 0) {
+                    Outcome::P
+                } else {
+                    Outcome::N
+                };
+                assert_eq!(Some(expected), eval.get_misere_outcome(nim::Nim::new(heaps)));
+            }
+        }
+    }
+}
+#[test]
+fn test_bottom_up_sweep_has_lower_processing_peak() {
+    let mut top_down: Evaluator<Kayles> = Evaluator::new();
+    top_down.get_nimber(Kayles { kayles: vec![60] });
+
+    let mut bottom_up: Evaluator<Kayles> = Evaluator::new();
+    for i in 0..=60 {
+        bottom_up.get_nimber(Kayles { kayles: vec![i] });
+    }
+    assert!(bottom_up.peak_processing_entries() > 0);
+    assert!(bottom_up.peak_processing_entries() < top_down.peak_processing_entries());
+}
+#[test]
+fn test_cancelled_evaluation_resumes() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    eval.stop();
+    assert!(eval.try_get_nimber(Kayles { kayles: vec![50] }).is_err());
+    eval.resume();
+    assert_eq!(KAYLES_NIMBERS[50], eval.get_nimber(Kayles { kayles: vec![50] }));
+}
+#[test]
+fn test_stopped_evaluators_still_finish_infallible_evaluations() {
+    let mut eval: Evaluator<Kayles> = Evaluator::with_nimber_ceiling(2);
+    eval.stop();
+    assert_eq!(KAYLES_NIMBERS[40], eval.get_nimber(Kayles { kayles: vec![40] }));
+    assert_eq!(Outcome::N, eval.get_outcome(Kayles { kayles: vec![41] }));
+    assert_eq!(vec![KAYLES_NIMBERS[42]], eval.get_nimbers_parallel(&[Kayles { kayles: vec![42] }]));
+    //the fallible api still reports the cancellation
+    assert!(eval.try_get_nimber(Kayles { kayles: vec![50] }).is_err());
+}
+#[test]
+fn test_clearing_mid_run_then_recomputing_yields_the_same_nimber() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let game = Kayles { kayles: vec![70] };
+    eval.get_nimber_or_estimate(game.clone(), Duration::ZERO);
+    assert!(!eval.data.is_empty());
+    eval.clear_cache();
+    assert_eq!(CacheStats::default(), eval.get_cache_stats());
+    assert_eq!(KAYLES_NIMBERS[70], eval.get_nimber(game.clone()));
+
+    assert!(eval.invalidate(&Kayles { kayles: vec![35] }));
+    assert!(!eval.invalidate(&Kayles { kayles: vec![35] }));
+    assert_eq!(KAYLES_NIMBERS[35], eval.get_nimber(Kayles { kayles: vec![35] }));
+    assert_eq!(KAYLES_NIMBERS[70], eval.get_nimber(game));
+}
+#[test]
+fn test_invalidated_entries_leave_the_stats_and_the_nimbers() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    eval.get_nimber(Kayles { kayles: vec![40] });
+    let stats = eval.get_cache_stats();
+    assert!(eval.invalidate(&Kayles { kayles: vec![20] }));
+    assert_eq!(stats.done - 1, eval.get_cache_stats().done);
+    assert_eq!(eval.index_map.len(), eval.get_cache_stats().total());
+    assert!(!eval.get_nimbers().contains(&(Kayles { kayles: vec![20] }, KAYLES_NIMBERS[20])));
+
+    eval.get_nimber(Kayles { kayles: vec![20] });
+    assert_eq!(stats, eval.get_cache_stats());
+    let (nimbers, _) = eval.nimbers_since(0);
+    assert_eq!(1, nimbers.iter().filter(|(game, _)| game.kayles == vec![20]).count());
+    assert_eq!(eval.get_nimbers().len(), stats.done);
+}
+#[test]
+fn test_evicted_slots_are_reclaimed_by_the_next_query() {
+    let mut eval: Evaluator<Kayles> = Evaluator::with_capacity(16);
+    let mut generation = 0;
+    let mut longest = 0;
+    for _ in 0..20 {
+        for (i, nimber) in KAYLES_NIMBERS.iter().enumerate().take(30) {
+            assert_eq!(*nimber, eval.get_nimber(Kayles { kayles: vec![i] }));
+            //only the slots evicted by the last query are left
+            longest = longest.max(eval.data.len());
+            let (nimbers, next_generation) = eval.nimbers_since(generation);
+            assert!(nimbers.iter().all(|(game, nimber)| KAYLES_NIMBERS[game.kayles[0]] == *nimber));
+            generation = next_generation;
+        }
+    }
+    //without reclaiming the slots the cache would grow with all 600 queries
+    assert!(longest < 64);
+    assert_eq!(eval.get_cache_stats().total(), eval.data.len());
+    for (game, index) in &eval.index_map {
+        assert!(Arc::ptr_eq(game, eval.data[*index].get_shared_game()));
+    }
+}
+#[test]
+fn test_spill_configured_by_the_builder_keeps_the_nimbers() {
+    let path = std::env::temp_dir().join(format!("evaluator_builder_spill_test_{}", std::process::id()));
+    let kayles = octal::named("kayles").unwrap();
+    let mut eval: Evaluator<OctalGame> = Evaluator::builder().spill(&path, 16).unwrap().nimber_ceiling(10).build();
+    for (i, nimber) in KAYLES_NIMBERS.iter().enumerate().take(70) {
+        assert_eq!(Ok(*nimber), eval.try_get_nimber(kayles.with_heaps(vec![i])));
+    }
+    assert!(eval.spill.as_ref().unwrap().spilled > 0);
+    std::fs::remove_file(&path).unwrap();
+}
+/// a spill whose storage is gone
+struct BrokenSpill;
+
+impl<G> SpillBackend<G> for BrokenSpill {
+    fn store(&mut self, _: &G, _: usize) -> std::io::Result<()> {
+        Err(std::io::Error::other("disk gone"))
+    }
+    fn load(&mut self, _: &G) -> std::io::Result<Option<usize>> {
+        Err(std::io::Error::other("disk gone"))
+    }
+    fn remove(&mut self, _: &G) -> std::io::Result<bool> {
+        Err(std::io::Error::other("disk gone"))
+    }
+    fn clear(&mut self) -> std::io::Result<()> {
+        Err(std::io::Error::other("disk gone"))
+    }
+}
+#[test]
+fn test_failing_spills_are_reported_and_replaced() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    eval.spill = Some(Spill::new(BrokenSpill, 16));
+    match eval.try_get_nimber(Kayles { kayles: vec![30] }) {
+        Err(EvalError::SpillFailed { message, .. }) => assert_eq!("disk gone", message),
+        other => panic!("expected a spill failure, got {:?}", other),
+    }
+    //the failure is reported once, afterwards the evicted nimbers are discarded
+    assert_eq!(Ok(KAYLES_NIMBERS[30]), eval.try_get_nimber(Kayles { kayles: vec![30] }));
+    assert_eq!(Ok(KAYLES_NIMBERS[50]), eval.try_get_nimber(Kayles { kayles: vec![50] }));
+
+    //the infallible api carries on without reporting the failure
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    eval.spill = Some(Spill::new(BrokenSpill, 16));
+    assert_eq!(KAYLES_NIMBERS[30], eval.get_nimber(Kayles { kayles: vec![30] }));
+    assert_eq!(Ok(KAYLES_NIMBERS[40]), eval.try_get_nimber(Kayles { kayles: vec![40] }));
+
+    //failures outside of evaluations are reported by the next one
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    eval.spill = Some(Spill::new(BrokenSpill, 16));
+    eval.clear_cache();
+    assert!(matches!(eval.try_get_nimber(Kayles { kayles: vec![] }), Err(EvalError::SpillFailed { .. })));
+    assert_eq!(Ok(0), eval.try_get_nimber(Kayles { kayles: vec![] }));
+}
+#[test]
+fn test_clearing_a_clone_keeps_the_spilled_nimbers_of_the_original() {
+    let path = std::env::temp_dir().join(format!("evaluator_shared_spill_test_{}", std::process::id()));
+    let kayles = octal::named("kayles").unwrap();
+    let mut eval: Evaluator<OctalGame> = Evaluator::with_spill(&path, 16).unwrap();
+    eval.get_nimber(kayles.with_heaps(vec![40]));
+    let cached = |eval: &Evaluator<OctalGame>| -> Vec<Option<usize>> {
+        (1..=40).map(|i| eval.get_cached_nimber(&kayles.with_heaps(vec![i]))).collect()
+    };
+    let before = cached(&eval);
+    assert!(eval.spill.as_ref().unwrap().spilled > 0);
+
+    let mut clone = eval.clone();
+    clone.clear_cache();
+    assert_eq!(KAYLES_NIMBERS[30], clone.get_nimber(kayles.with_heaps(vec![30])));
+    assert_eq!(before, cached(&eval));
+    std::fs::remove_file(path).unwrap();
+}
+#[test]
+fn test_move_orders_agree_and_are_reproducible() {
+    let orders: Vec<MoveOrder<Kayles>> = vec![
+        MoveOrder::LargestMaxNimberFirst,
+        MoveOrder::SmallestMaxNimberFirst,
+        MoveOrder::Generated,
+        MoveOrder::Custom(Arc::new(|g: &Kayles| g.kayles.len() as i64)),
+    ];
+    for order in orders {
+        let mut move_evaluations = vec![];
+        for _ in 0..2 {
+            let mut eval: Evaluator<Kayles> = Evaluator::new();
+            eval.set_move_order(order.clone());
+            assert_eq!(KAYLES_NIMBERS[40], eval.get_nimber(Kayles { kayles: vec![40] }));
+            move_evaluations.push(eval.move_evaluations());
+        }
+        assert_eq!(move_evaluations[0], move_evaluations[1], "{:?}", order);
+    }
+}
+#[test]
+fn test_metrics_count_a_single_query() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    eval.get_nimber(Kayles { kayles: vec![30] });
+    let metrics = eval.metrics();
+    assert!(metrics.cache_misses > 0);
+    assert_eq!(eval.move_evaluations(), metrics.move_evaluations);
+
+    eval.reset_metrics();
+    eval.get_nimber(Kayles { kayles: vec![30] });
+    assert_eq!(
+        Metrics {
+            cache_hits: 1,
+            cache_misses: 0,
+            move_evaluations: 0
+        },
+        eval.metrics()
+    );
+}
+#[test]
+fn test_iterative_deepening_reaches_the_nimber() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let game = Kayles { kayles: vec![30] };
+    assert_eq!(Err(EvalError::DepthExceeded), eval.try_get_nimber_with_depth(game.clone(), 0));
+    let mut depth = 1;
+    while eval.get_nimber_with_depth(game.clone(), depth).is_none() {
+        depth += 1;
+    }
+    assert!(depth > 1);
+    //known nimbers are free at any depth
+    assert_eq!(Ok(KAYLES_NIMBERS[30]), eval.try_get_nimber_with_depth(game, 0));
+    assert_eq!(KAYLES_NIMBERS[29], eval.get_nimber(Kayles { kayles: vec![29] }));
+}
+#[test]
+fn test_p_positions_are_cheaper_than_nimbers() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    for (i, nimber) in KAYLES_NIMBERS.iter().enumerate().take(40) {
+        assert_eq!(Some(*nimber == 0), eval.is_p_position(Kayles { kayles: vec![i] }));
+    }
+
+    let mut outcome_only: Evaluator<Kayles> = Evaluator::new();
+    assert_eq!(Some(false), outcome_only.is_p_position(Kayles { kayles: vec![70] }));
+    let mut full: Evaluator<Kayles> = Evaluator::new();
+    full.get_nimber(Kayles { kayles: vec![70] });
+    assert!(outcome_only.move_evaluations() < full.move_evaluations());
+}
+#[test]
+fn test_checkpoints_see_the_progress_and_can_cancel() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let checkpoints: Arc<Mutex<Vec<usize>>> = Arc::new(Mutex::new(vec![]));
+    let seen = checkpoints.clone();
+    eval.set_checkpoint_hook(
+        Duration::ZERO,
+        Arc::new(move |eval: &Evaluator<Kayles>| {
+            let mut seen = seen.lock().unwrap();
+            seen.push(eval.get_cache_stats().done);
+            if seen.len() == 3 {
+                eval.stop();
+            }
+        }),
+    );
+    let game = Kayles { kayles: vec![70] };
+    assert_eq!(
+        Err(EvalError::Cancelled {
+            reason: Some("stopped".to_string())
+        }),
+        eval.try_get_nimber(game.clone())
+    );
+    assert_eq!(3, checkpoints.lock().unwrap().len());
+
+    eval.resume();
+    assert_eq!(KAYLES_NIMBERS[70], eval.get_nimber(game));
+    let checkpoints = checkpoints.lock().unwrap();
+    assert!(checkpoints.len() > 3);
+    assert!(checkpoints.windows(2).all(|pair| pair[0] <= pair[1]));
+}
+#[test]
+fn test_cancellation_is_distinguished_from_exceeding_the_bound() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    //kayles 27 has nimber 8
+    assert_eq!(Err(EvalError::BoundExceeded), eval.try_get_bounded_nimber(Kayles { kayles: vec![27] }, 7));
+    let token = eval.cancellation_token();
+    token.cancel("out of time");
+    assert_eq!(
+        Err(EvalError::Cancelled {
+            reason: Some("out of time".to_string())
+        }),
+        eval.try_get_bounded_nimber(Kayles { kayles: vec![50] }, 7)
+    );
+    token.reset();
+    eval.cancel_flag().store(true, std::sync::atomic::Ordering::Relaxed);
+    assert_eq!(
+        Err(EvalError::Cancelled { reason: None }),
+        eval.try_get_bounded_nimber(Kayles { kayles: vec![50] }, 7)
+    );
+    eval.resume();
+    assert_eq!(Ok(KAYLES_NIMBERS[50]), eval.try_get_nimber(Kayles { kayles: vec![50] }));
+}
+#[test]
+fn test_single_heap_table_with_progress_and_cancellation() {
+    let make = |i| Kayles { kayles: vec![i] };
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let cancel_flag = eval.cancel_flag();
+    let mut reported = vec![];
+    let table = eval.solve_single_heap_table(make, 80, |i| {
+        reported.push(i);
+        if i == 30 {
+            cancel_flag.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+    });
+    assert_eq!(&KAYLES_NIMBERS[..=30], table.nimbers());
+    assert_eq!((0..=30).collect::<Vec<usize>>(), reported);
+
+    eval.resume();
+    let table = eval.solve_single_heap_table(make, 80, |_| {});
+    assert_eq!(&KAYLES_NIMBERS[..=80], table.nimbers());
+    assert_eq!(Some(KAYLES_NIMBERS[80]), table.get(80));
+}
+#[test]
+fn test_bounded_kayles_nimbers() {
+    let mut eval: Evaluator<Bounded<Kayles>> = Evaluator::new();
+    let bounded = |n, remaining| Bounded::new(Kayles { kayles: vec![n] }, remaining);
+
+    assert_eq!(0, eval.get_nimber(bounded(10, 0)));
+    assert_eq!(1, eval.get_nimber(bounded(10, 1)));
+    //a row of n pins can never last more than n moves
+    for (n, nimber) in KAYLES_NIMBERS.iter().enumerate().take(20) {
+        assert_eq!(*nimber, eval.get_nimber(bounded(n, n)));
+    }
+}
+#[test]
+fn test_kayles_rows_match_their_decomposition() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let split = |g: &Kayles| g.clone().get_parts();
+    for (a, nimber_a) in KAYLES_NIMBERS.iter().enumerate().take(15) {
+        for (b, nimber_b) in KAYLES_NIMBERS.iter().enumerate().take(15) {
+            assert_eq!(
+                Some(Ok(nimber_a ^ nimber_b)),
+                eval.verify_decomposition(Kayles { kayles: vec![a, b] }, split)
+            );
+        }
+    }
+    //a row of 4 pins is not the sum of rows of 1 and 3 pins
+    assert_eq!(
+        Some(Err(DecompositionMismatch { whole: 1, by_parts: 2 })),
+        eval.verify_decomposition(Kayles { kayles: vec![4] }, |_| vec![
+            Kayles { kayles: vec![1] },
+            Kayles { kayles: vec![3] }
+        ])
+    );
+}
+#[test]
+fn test_rule_out_stops_once_the_candidate_is_the_only_possible_nimber() {
+    //the subtraction game {1, 2, 4, 5}, its nimbers are the heap size mod 3
+    let game = Counted::new(0, vec![1, 2, 4, 5], Arc::new(Counters::default())).with_max_nimber(2);
+    let mut eval: Evaluator<Counted> = Evaluator::new();
+    for heap in 0..30 {
+        assert_eq!(heap % 3, eval.get_nimber(game.with_heap(heap)));
+    }
+    let move_evaluations = eval.move_evaluations();
+    //the moves to 25 and 26 rule out 1 and 2, so the moves to 28 and 29 are never evaluated
+    assert_eq!(0, eval.get_nimber(game.with_heap(30)));
+    assert_eq!(move_evaluations + 2, eval.move_evaluations());
+}
+#[test]
+fn test_ruled_out_nimbers_of_processing_and_done_positions() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let row = Kayles { kayles: vec![5] };
+    assert_eq!(None, eval.ruled_out_nimbers(&row));
+    assert_eq!(Err(EvalError::BoundExceeded), eval.try_get_bounded_nimber(row.clone(), 2));
+    //the largest moves come first, so the candidates are reached out of order
+    let processing = eval.ruled_out_nimbers(&row).unwrap();
+    assert_eq!(vec![2, 0, 3, 1], processing.ruled_out);
+    assert_eq!(None, processing.nimber);
+    assert_eq!(KAYLES_NIMBERS[5], eval.get_nimber(row.clone()));
+    let done = eval.ruled_out_nimbers(&row).unwrap();
+    assert_eq!(processing.ruled_out, done.ruled_out);
+    assert_eq!(Some(KAYLES_NIMBERS[5]), done.nimber);
+}
+#[test]
+fn test_iterated_nimbers_are_the_done_entries() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    assert_eq!(KAYLES_NIMBERS[20], eval.get_nimber(Kayles { kayles: vec![20] }));
+    assert_eq!(eval.get_cache_stats().done, eval.iter_nimbers().count());
+    for (game, nimber) in eval.get_nimbers() {
+        assert_eq!(KAYLES_NIMBERS[game.kayles[0]], nimber);
+    }
+}
+#[test]
+fn test_iterative_kayles_nimbers_match_recursive_nimbers() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    for (n, nimber) in KAYLES_NIMBERS.iter().enumerate() {
+        assert_eq!(Some(*nimber), eval.get_nimber_iteratively(Kayles { kayles: vec![n] }));
+    }
+    let mut recursive: Evaluator<Kayles> = Evaluator::new();
+    let sum = Kayles { kayles: vec![40, 23, 7] };
+    assert_eq!(Some(recursive.get_nimber(sum.clone())), Evaluator::new().get_nimber_iteratively(sum));
+}
+#[test]
+fn test_iterative_evaluation_of_games_too_deep_for_the_recursion() {
+    //every position only has moves to the next smaller heaps, so the recursion would nest once per heap
+    let game = SubtractionGame::new(0, vec![1, 2]);
+    let mut eval: Evaluator<SubtractionGame> = Evaluator::new();
+    assert_eq!(Some(50_000 % 3), eval.get_nimber_iteratively(game.with_heap(50_000)));
+}
+#[test]
+fn test_bounded_sums_skip_parts_exceeding_the_bound() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    for (a, a_nimber) in KAYLES_NIMBERS.iter().enumerate().take(16) {
+        for (b, b_nimber) in KAYLES_NIMBERS.iter().enumerate().take(16) {
+            let nimber = a_nimber ^ b_nimber ^ KAYLES_NIMBERS[5];
+            for bound in 0..10 {
+                //a nimber above the bound is only returned if it is known anyway
+                match eval.try_get_bounded_nimber(Kayles { kayles: vec![a, b, 5] }, bound) {
+                    Ok(bounded_nimber) => assert_eq!(nimber, bounded_nimber),
+                    Err(error) => assert!(error == EvalError::BoundExceeded && nimber > bound),
+                }
+            }
+        }
+    }
+    //the row of 27 has the nimber 8, which no bit of the other row can cancel
+    let sum = Kayles { kayles: vec![27, 2] };
+    let mut bounded: Evaluator<Kayles> = Evaluator::new();
+    assert_eq!(Err(EvalError::BoundExceeded), bounded.try_get_bounded_nimber(sum.clone(), 3));
+    let mut exact: Evaluator<Kayles> = Evaluator::new();
+    assert_eq!(8 ^ 2, exact.get_nimber(sum));
+    assert!(bounded.move_evaluations() < exact.move_evaluations());
+}
+#[test]
+fn test_cache_stats_are_displayed_and_add_up() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    assert_eq!(Err(EvalError::BoundExceeded), eval.try_get_bounded_nimber(Kayles { kayles: vec![9] }, 1));
+    let stats = eval.get_cache_stats();
+    assert!(stats.processing > 0);
+    assert_eq!(stats.stubs + stats.processing + stats.done, stats.total());
+    assert_eq!(
+        format!("stubs: {}, processing: {}, done: {}", stats.stubs, stats.processing, stats.done),
+        stats.to_string()
+    );
+}
+#[test]
+fn test_forked_caches_are_independent_of_the_original() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    assert_eq!(KAYLES_NIMBERS[20], eval.get_nimber(Kayles { kayles: vec![20] }));
+    let mut evaluations = vec![];
+    for _ in 0..2 {
+        let mut fork = eval.fork_cache();
+        assert_eq!(Metrics::default(), fork.metrics());
+        assert_eq!(KAYLES_NIMBERS[40], fork.get_nimber(Kayles { kayles: vec![40] }));
+        evaluations.push(fork.move_evaluations());
+    }
+    assert_eq!(evaluations[0], evaluations[1]);
+    assert!(!eval.contains(&Kayles { kayles: vec![40] }));
+    let fork = eval.fork_cache();
+    eval.stop();
+    assert_eq!(KAYLES_NIMBERS[30], fork.fork_cache().get_nimber(Kayles { kayles: vec![30] }));
+}
+#[test]
+fn test_tabulate_grid_of_two_kayles_rows() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let keys: Vec<(usize, usize)> = (0..12).flat_map(|a| (0..12).map(move |b| (a, b))).collect();
+    let mut reported = 0;
+    let grid = eval.tabulate_grid(
+        keys.clone(),
+        |(a, b)| Kayles { kayles: vec![*a, *b] },
+        |_| reported += 1,
+    );
+    assert_eq!(keys.len(), reported);
+    for (a, b) in keys {
+        assert_eq!(KAYLES_NIMBERS[a] ^ KAYLES_NIMBERS[b], grid[&(a, b)]);
+    }
+}
+
+/// kayles played on a single row with gaps, which is only split into segments if `split` is set
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+struct PinRow {
+    pins: Vec<bool>,
+    split: bool,
+}
+
+impl Impartial<PinRow> for PinRow {
+    fn get_parts(self) -> Vec<PinRow> {
+        vec![self]
+    }
+
+    fn get_max_nimber(&self) -> usize {
+        self.pins.iter().filter(|pin| **pin).count()
+    }
+
+    fn get_unique_moves(&self) -> Vec<PinRow> {
+        let mut moves = vec![];
+        for i in 0..self.pins.len() {
+            for width in 1..=2 {
+                if i + width <= self.pins.len() && self.pins[i..i + width].iter().all(|pin| *pin) {
+                    let mut pins = self.pins.clone();
+                    pins[i..i + width].fill(false);
+                    moves.push(PinRow { pins, split: self.split });
+                }
+            }
+        }
+        moves
+    }
+
+    fn decompose(&self) -> Option<Vec<PinRow>> {
+        let segments: Vec<PinRow> = self
+            .pins
+            .split(|pin| !*pin)
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| PinRow { pins: segment.to_vec(), split: true })
+            .collect();
+        let is_single_segment = segments.len() == 1 && segments[0].pins.len() == self.pins.len();
+        if !self.split || is_single_segment {
+            return None;
+        }
+        Some(segments)
+    }
+}
+
+#[test]
+fn test_decomposed_rows_are_cheaper_and_agree() {
+    let row = |split| {
+        let mut pins = vec![true; 12];
+        pins[6] = false;
+        PinRow { pins, split }
+    };
+    let mut whole: Evaluator<PinRow> = Evaluator::new();
+    let mut decomposed: Evaluator<PinRow> = Evaluator::new();
+
+    assert_eq!(KAYLES_NIMBERS[6] ^ KAYLES_NIMBERS[5], whole.get_nimber(row(false)));
+    assert_eq!(KAYLES_NIMBERS[6] ^ KAYLES_NIMBERS[5], decomposed.get_nimber(row(true)));
+    assert!(decomposed.move_evaluations() * 10 < whole.move_evaluations());
+    assert!(decomposed.data.len() * 10 < whole.data.len());
+}
+
+static FLAKY_MOVE_GENERATIONS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// a broken game whose moves change every time they are generated
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+struct Flaky(usize);
+
+impl Impartial<Flaky> for Flaky {
+    fn get_parts(self) -> Vec<Flaky> {
+        vec![self]
+    }
+
+    fn get_max_nimber(&self) -> usize {
+        self.0
+    }
+
+    fn get_unique_moves(&self) -> Vec<Flaky> {
+        let generation = FLAKY_MOVE_GENERATIONS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        (0..self.0).skip(generation % 2).map(Flaky).collect()
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "different moves for equal games")]
+fn test_nondeterministic_moves_are_detected_after_eviction() {
+    let mut eval: Evaluator<Flaky> = Evaluator::new();
+    eval.get_nimber(Flaky(1));
+    //forget every entry as if they all got evicted
+    eval.index_map.clear();
+    eval.get_nimber(Flaky(1));
+}
+#[test]
+fn test_estimate_on_timeout_then_exact() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let game = Kayles { kayles: vec![70] };
+    match eval.get_nimber_or_estimate(game.clone(), Duration::ZERO) {
+        NimberResult::Bounds { lower, upper } => {
+            assert!(lower <= KAYLES_NIMBERS[70] && KAYLES_NIMBERS[70] <= upper)
+        }
+        NimberResult::Exact(_) => panic!("the evaluation should have timed out"),
+    }
+    assert_eq!(
+        NimberResult::Exact(KAYLES_NIMBERS[70]),
+        eval.get_nimber_or_estimate(game, Duration::from_secs(60))
+    );
+}
+#[test]
+fn test_timed_out_evaluation_resumes() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let game = Kayles { kayles: vec![80] };
+    assert_eq!(None, eval.get_nimber_with_timeout(game.clone(), Duration::ZERO));
+    assert!(eval.get_cache_stats().processing > 0);
+    assert_eq!(KAYLES_NIMBERS[80], eval.get_nimber(game));
+}
+#[test]
+fn test_new_max_nimbers_are_reported() {
+    let records = Arc::new(Mutex::new(vec![]));
+    let kayles = octal::named("kayles").unwrap();
+    let mut eval: Evaluator<OctalGame> = Evaluator::new();
+    let reported = records.clone();
+    eval.on_new_max_nimber(Arc::new(move |game: &OctalGame, nimber| {
+        reported.lock().unwrap().push((game.label(), nimber))
+    }));
+    for i in 1..40 {
+        eval.get_nimber(kayles.with_heaps(vec![i]));
+    }
+    let records = records.lock().unwrap();
+    assert!(records.windows(2).all(|pair| pair[0].1 < pair[1].1));
+    assert_eq!(("[1]".to_string(), 1), records[0]);
+    assert_eq!(("[27]".to_string(), 8), *records.last().unwrap());
+}
+#[test]
+fn test_saved_evaluator_resumes_after_loading() {
+    let path = std::env::temp_dir().join(format!("evaluator_save_test_{}", std::process::id()));
+    let kayles = octal::named("kayles").unwrap();
+    let mut eval: Evaluator<OctalGame> = Evaluator::new();
+    eval.get_nimber_or_estimate(kayles.with_heaps(vec![60]), Duration::from_millis(5));
+    eval.save_to_path(&path).unwrap();
+
+    let mut loaded: Evaluator<OctalGame> = Evaluator::load_from_path(&path).unwrap();
+    assert_eq!(eval, loaded);
+    //every game is still stored only once, shared by its entry and its key
+    for (game, index) in &loaded.index_map {
+        assert!(Arc::ptr_eq(game, loaded.data[*index].get_shared_game()));
+    }
+    assert_eq!(KAYLES_NIMBERS[60], loaded.get_nimber(kayles.with_heaps(vec![60])));
+    std::fs::remove_file(&path).unwrap();
+}
+#[test]
+fn test_loading_rejects_other_format_versions() {
+    let path = std::env::temp_dir().join(format!("evaluator_version_test_{}", std::process::id()));
+    std::fs::write(&path, 2u32.to_le_bytes()).unwrap();
+    match Evaluator::<OctalGame>::load_from_path(&path) {
+        Err(PersistenceError::VersionMismatch { found: 2, .. }) => (),
+        other => panic!("expected a version mismatch, got {:?}", other),
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+#[test]
+fn test_threaded_kayles_nimbers_match_serial_nimbers() {
+    let mut serial: Evaluator<Kayles> = Evaluator::new();
+    let mut threaded: Evaluator<Kayles> = Evaluator::with_thread_count(4);
+    for (i, expected) in KAYLES_NIMBERS.iter().enumerate().take(70) {
+        let nimber = threaded.get_nimber(Kayles { kayles: vec![i] });
+        assert_eq!(*expected, nimber);
+        assert_eq!(serial.get_nimber(Kayles { kayles: vec![i] }), nimber);
+    }
+}
+#[test]
+fn test_threads_ruling_out_the_moves_of_fresh_rows_match_the_kayles_sequence() {
+    //every row is queried on an empty cache, so the moves of the row are ruled out on the threads
+    for threads in [2, 3, 8] {
+        for i in [40, 77, 104] {
+            let mut threaded: Evaluator<Kayles> = Evaluator::with_thread_count(threads);
+            assert_eq!(KAYLES_NIMBERS[i], threaded.get_nimber(Kayles { kayles: vec![i] }));
+            //the cache got the rows the threads found
+            for (j, expected) in KAYLES_NIMBERS.iter().enumerate().take(i) {
+                let nimber = threaded.get_cached_nimber(&Kayles { kayles: vec![j] });
+                assert!(nimber.is_none_or(|nimber| nimber == *expected));
+            }
+        }
+    }
+}
+#[test]
+fn test_threads_ruling_out_moves_expand_every_position_once() {
+    for threads in [2, 4, 16] {
+        let counters = Arc::new(Counters::default());
+        let game = Counted::new(0, vec![1, 2, 3, 5, 8], counters.clone());
+        let mut threaded: Evaluator<Counted> = Evaluator::with_thread_count(threads);
+        let mut serial: Evaluator<Counted> = Evaluator::new();
+        let nimber = serial.get_nimber(Counted::new(300, vec![1, 2, 3, 5, 8], Arc::new(Counters::default())));
+        assert_eq!(nimber, threaded.get_nimber(game.with_heap(300)));
+        assert_eq!((1..=300).collect::<Vec<usize>>(), counters.expanded_heaps());
+    }
+}
+#[test]
+fn test_threaded_parts_of_kayles_splits_match_serial_parts() {
+    //rows split into several components, the first and the last component of every split cancel out
+    let splits: Vec<Vec<usize>> = (0..12).map(|i| vec![3 * i + 1, 40 - 3 * i, 17 + i, 60 - i, 3 * i + 1]).collect();
+    for bound in [0, 1, 3, 6, usize::MAX] {
+        let mut serial: Evaluator<Kayles> = Evaluator::new();
+        let mut threaded: Evaluator<Kayles> = Evaluator::with_thread_count(4);
+        for kayles in &splits {
+            let expected = kayles.iter().fold(0, |nimber, i| nimber ^ KAYLES_NIMBERS[*i]);
+            let nimber = threaded.try_get_bounded_nimber(Kayles { kayles: kayles.clone() }, bound);
+            assert_eq!(serial.try_get_bounded_nimber(Kayles { kayles: kayles.clone() }, bound), nimber);
+            match nimber {
+                Ok(nimber) => assert_eq!(expected, nimber),
+                Err(error) => {
+                    assert_eq!(EvalError::BoundExceeded, error);
+                    assert!(expected > bound);
+                }
+            }
+        }
+    }
+}
+#[test]
+fn test_threaded_sums_match_serial_sums() {
+    let mut serial: Evaluator<Kayles> = Evaluator::new();
+    let mut threaded: Evaluator<Kayles> = Evaluator::with_thread_count(4);
+    let sums = [vec![20, 31, 20, 45, 7], vec![3, 3], vec![12, 50, 33, 50, 61, 2], vec![64, 1]];
+    for kayles in sums {
+        let expected = kayles.iter().fold(0, |nimber, i| nimber ^ KAYLES_NIMBERS[*i]);
+        let nimber = threaded.get_nimber(Kayles { kayles: kayles.clone() });
+        assert_eq!(expected, nimber);
+        assert_eq!(serial.get_nimber(Kayles { kayles }), nimber);
+    }
+}
+#[test]
+fn test_winning_moves_lead_to_p_positions() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    for i in 1..40 {
+        let winning_move = eval.get_winning_move(Kayles { kayles: vec![i] }).unwrap();
+        assert_eq!(0, eval.get_nimber(winning_move));
+    }
+    assert_eq!(None, eval.get_winning_move(Kayles { kayles: vec![0] }));
+    assert_eq!(None, eval.get_winning_move(Kayles { kayles: vec![9, 9] }));
+}
+#[test]
+fn test_subtraction_game_1_3_4_nimbers() {
+    let period = [0, 1, 0, 1, 2, 3, 2];
+    let game = SubtractionGame::new(0, vec![1, 3, 4]);
+    let mut eval: Evaluator<SubtractionGame> = Evaluator::new();
+    for heap in 0..100 {
+        assert_eq!(period[heap % 7], eval.get_nimber(game.with_heap(heap)));
+    }
+}
+#[test]
+fn test_octal_games() {
+    let kayles = octal::named("kayles").unwrap();
+    let mut eval: Evaluator<OctalGame> = Evaluator::new();
+    for (heap, nimber) in KAYLES_NIMBERS.iter().enumerate().take(40) {
+        assert_eq!(*nimber, eval.get_nimber(kayles.with_heaps(vec![heap])));
+    }
+
+    let dawsons_chess = octal::named("dawsons_chess").unwrap();
+    assert_eq!(octal::named("0.137").unwrap().code, dawsons_chess.code);
+    let nimbers = [
+        0, 1, 1, 2, 0, 3, 1, 1, 0, 3, 3, 2, 2, 4, 0, 5, 2, 2, 3, 3, 0, 1, 1, 3, 0, 2, 1, 1, 0, 4, 5, 2, 7, 4,
+    ];
+    let mut eval: Evaluator<OctalGame> = Evaluator::new();
+    for (heap, nimber) in nimbers.iter().enumerate() {
+        assert_eq!(*nimber, eval.get_nimber(dawsons_chess.with_heaps(vec![heap])));
+    }
+    assert_eq!(Err(OctalCodeError::InvalidDigit('8')), OctalGame::parse_code("0.18"));
+    assert_eq!(Err(OctalCodeError::MissingPrefix), OctalGame::parse_code("137"));
+    assert_eq!(Err(OctalCodeError::UnknownName), octal::named("unknown").map(|game| game.code));
+    assert_eq!(Err(OctalCodeError::InvalidDigit('9')), octal::named("0.19").map(|game| game.code));
+}
+#[test]
+fn test_nimber_sequence_streams_until_cancelled() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let streamed: Vec<Option<usize>> = eval.nimber_sequence(|i| Kayles { kayles: vec![i] }).take(50).collect();
+    let expected: Vec<Option<usize>> = KAYLES_NIMBERS[..50].iter().map(|nimber| Some(*nimber)).collect();
+    assert_eq!(expected, streamed);
+
+    eval.stop();
+    let streamed: Vec<Option<usize>> = eval.nimber_sequence(|i| Kayles { kayles: vec![i + 60] }).collect();
+    assert_eq!(vec![None], streamed);
+}
+#[test]
+fn test_nimber_proofs_are_valid_by_the_mex_rule() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    for (i, nimber) in KAYLES_NIMBERS.iter().enumerate().take(30) {
+        let game = Kayles { kayles: vec![i] };
+        let proof: NimberProof<Kayles> = eval.explain_nimber(game.clone()).unwrap();
+        assert_eq!(*nimber, proof.nimber);
+        let moves = game.get_unique_moves();
+        for (k, witness) in proof.witnesses.iter().enumerate() {
+            assert!(moves.contains(witness));
+            assert_eq!(k, eval.get_nimber(witness.clone()));
+        }
+        assert_eq!(moves.len(), proof.move_nimbers.len());
+        assert!(proof.move_nimbers.iter().all(|(_, move_nimber)| *move_nimber != proof.nimber));
+    }
+}
+#[test]
+fn test_find_period() {
+    assert_eq!(Some((0, 2)), find_period(&[0, 1, 0, 1]));
+    assert_eq!(Some((1, 2)), find_period(&[5, 0, 1, 0, 1]));
+    assert_eq!(Some((0, 1)), find_period(&[3, 3]));
+    //a period has to repeat twice to be confirmed
+    assert_eq!(None, find_period(&[0, 1, 2, 0, 1]));
+    assert_eq!(None, find_period(&[]));
+}
+#[test]
+fn test_subtraction_game_period() {
+    let game = SubtractionGame::new(0, vec![1, 3, 4]);
+    let mut eval: Evaluator<SubtractionGame> = Evaluator::new();
+    assert_eq!(None, eval.get_period(|heap| game.with_heap(heap), 8));
+    assert_eq!(Some((0, 7)), eval.get_period(|heap| game.with_heap(heap), 30));
+}
+#[test]
+fn test_sequence_is_extended_until_it_is_periodic() {
+    let game = SubtractionGame::new(0, vec![1, 3, 4]);
+    let mut eval: Evaluator<SubtractionGame> = Evaluator::new();
+    assert_eq!(None, eval.extend_until_periodic(|heap| game.with_heap(heap), 9, 5, 12));
+    assert_eq!(Some((0, 7)), eval.extend_until_periodic(|heap| game.with_heap(heap), 9, 5, 100));
+    eval.cancellation_token().cancel("out of time");
+    eval.clear_cache();
+    assert_eq!(None, eval.extend_until_periodic(|heap| game.with_heap(heap), 9, 5, 100));
+}
+#[test]
+fn test_progress_is_reported_until_the_end() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let mut reports: Vec<CacheStats> = vec![];
+    let nimber = eval.get_nimber_with_progress(Kayles { kayles: vec![60] }, Duration::ZERO, |stats| {
+        reports.push(stats)
+    });
+    assert_eq!(Some(KAYLES_NIMBERS[60]), nimber);
+    assert!(reports.len() > 1);
+    assert_eq!(eval.get_cache_stats(), *reports.last().unwrap());
+}
+
+/// nim heaps which all share the same hash
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct Colliding {
+    heaps: Vec<usize>,
+}
+
+impl std::hash::Hash for Colliding {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        0.hash(state)
+    }
+}
+
+impl Impartial<Colliding> for Colliding {
+    fn get_parts(self) -> Vec<Colliding> {
+        self.heaps.iter().map(|heap| Colliding { heaps: vec![*heap] }).collect()
+    }
+
+    fn get_max_nimber(&self) -> usize {
+        self.heaps.iter().sum()
+    }
+
+    fn get_unique_moves(&self) -> Vec<Colliding> {
+        (0..self.heaps[0]).map(|heap| Colliding { heaps: vec![heap] }).collect()
+    }
+}
+
+#[test]
+fn test_pairs_cancel_despite_hash_collisions() {
+    let mut eval: Evaluator<Colliding> = Evaluator::new();
+    assert_eq!(3, eval.get_nimber(Colliding { heaps: vec![7, 3, 7] }));
+    assert_eq!(0, eval.get_nimber(Colliding { heaps: vec![5, 2, 5, 2] }));
+    //the cancelled heaps never got evaluated
+    assert!(!eval.is_known(&Colliding { heaps: vec![7] }));
+    assert!(!eval.is_known(&Colliding { heaps: vec![5] }));
+}
+
+#[test]
+fn test_cancelling_symmetric_parts_keeps_the_nimber() {
+    let row = |n| Kayles { kayles: vec![n] };
+    let mut parts = vec![row(5), row(2), row(5), row(3), row(2), row(2)];
+    let mut eval = Evaluator::new();
+    let mut nimber = 0;
+    for part in parts.clone() {
+        nimber ^= eval.get_nimber(part);
+    }
+    cancel_symmetric_parts(&mut parts);
+    assert_eq!(vec![row(2), row(3)], parts);
+    assert_eq!(Some(nimber), eval.get_nimber_by_parts(parts));
+}
+
+#[test]
+fn test_memory_estimate_grows_with_the_cache() {
+    let kayles = octal::named("kayles").unwrap();
+    let mut eval: Evaluator<OctalGame> = Evaluator::new();
+    let mut estimate = eval.estimated_memory_bytes();
+    for n in [10, 20, 40] {
+        eval.get_nimber(kayles.with_heaps(vec![n]));
+        let new_estimate = eval.estimated_memory_bytes();
+        assert!(new_estimate > estimate);
+        estimate = new_estimate;
+    }
+    //the boards of the cached games are included
+    let CacheStats { stubs, processing, done } = eval.get_cache_stats();
+    assert!(estimate > (stubs + processing + done) * std::mem::size_of::<usize>() * 4);
+}
+
+#[test]
+fn test_wythoff_p_positions_follow_the_beatty_sequence() {
+    //the k-th p-position is (floor(k * phi), floor(k * phi) + k)
+    let phi = (1.0 + 5f64.sqrt()) / 2.0;
+    let p_positions: Vec<(usize, usize)> = (0..20)
+        .map(|k| ((k as f64 * phi).floor() as usize, (k as f64 * phi).floor() as usize + k))
+        .collect();
+    let mut eval = Evaluator::new();
+    for a in 0..25 {
+        for b in a..25 {
+            let nimber = eval.get_nimber(Wythoff::new(b, a));
+            assert_eq!(p_positions.contains(&(a, b)), nimber == 0, "({a}, {b}) has nimber {nimber}");
+        }
+    }
+    //the nimbers away from the p-positions are those of the known table
+    assert_eq!(5, eval.get_nimber(Wythoff::new(1, 4)));
+    assert_eq!(6, eval.get_nimber(Wythoff::new(3, 3)));
+}
+#[test]
+fn test_mirrored_wythoff_positions_are_equal() {
+    assert_eq!(Wythoff::new(1, 4), Wythoff::new(4, 1));
+    assert_eq!(Wythoff::new(1, 4).get_unique_moves(), Wythoff::new(4, 1).get_unique_moves());
+    assert!(Wythoff::new(5, 2).get_unique_moves().contains(&Wythoff::new(0, 3)));
+    let mut eval = Evaluator::new();
+    assert_eq!(5, eval.get_nimber(Wythoff::new(4, 1)));
+    assert_eq!(Some(5), eval.get_cached_nimber(&Wythoff::new(1, 4)));
+}
+
+#[test]
+fn test_built_evaluators_agree_with_the_default_one() {
+    let mut default: Evaluator<Kayles> = Evaluator::new();
+    let mut built: Evaluator<Kayles> = Evaluator::builder()
+        .threads(2)
+        .capacity(200)
+        .move_order(MoveOrder::SmallestMaxNimberFirst)
+        .checkpoint_hook(Duration::from_secs(60), Arc::new(|_| {}))
+        .build();
+    for n in [8, 20, 31] {
+        let row = Kayles { kayles: vec![n, 4] };
+        assert_eq!(default.get_nimber(row.clone()), built.get_nimber(row));
+    }
+}
+
+#[test]
+fn test_colliding_games_evaluate_on_threads() {
+    //every move of every game lands in the same bucket of the cache as the game itself
+    let games: Vec<Colliding> = (0..40).map(|heap| Colliding { heaps: vec![heap, heap / 2] }).collect();
+    let mut threaded: Evaluator<Colliding> = Evaluator::with_thread_count(4);
+    let nimbers = threaded.get_nimbers_parallel(&games);
+    for (game, nimber) in games.iter().zip(nimbers) {
+        assert_eq!(game.heaps[0] ^ game.heaps[1], nimber);
+        assert_eq!(nimber, threaded.get_nimber(game.clone()));
+    }
+}
+
+#[test]
+fn test_cooperative_evaluation_polls_and_cancels() {
+    let row = Kayles { kayles: vec![104] };
+    let mut eval: Evaluator<Kayles> = Evaluator::builder().cancellation_poll_interval(1).build();
+    let mut polls = 0;
+    assert_eq!(None, eval.get_nimber_cooperative(row.clone(), |_| {
+        polls += 1;
+        false
+    }));
+    assert_eq!(1, polls);
+    //the cancelled evaluation continues where it stopped
+    let mut stats = vec![];
+    assert_eq!(Some(KAYLES_NIMBERS[104]), eval.get_nimber_cooperative(row, |s| {
+        stats.push(s);
+        true
+    }));
+    assert!(!stats.is_empty());
+    assert!(stats.windows(2).all(|pair| pair[0].done <= pair[1].done));
+}
+
+#[test]
+fn test_sequence_string_lists_the_nimbers() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let make = |n| Kayles { kayles: vec![n] };
+    assert_eq!(Some("0, 1, 2, 3, 1, 4, 3, 2".to_string()), eval.sequence_string(make, 8));
+    assert_eq!(Some(String::new()), eval.sequence_string(make, 0));
+    eval.stop();
+    assert_eq!(None, eval.sequence_string(make, 20));
+}
+
+#[test]
+fn test_cached_nimbers_are_read_without_evaluating() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    assert_eq!(4, eval.get_nimber(Kayles { kayles: vec![9] }));
+    let stats = eval.get_cache_stats();
+    assert_eq!(Some(4), eval.get_cached_nimber(&Kayles { kayles: vec![9] }));
+    assert_eq!(Some(4 ^ 2), eval.get_cached_nimber(&Kayles { kayles: vec![9, 7] }));
+    assert_eq!(None, eval.get_cached_nimber(&Kayles { kayles: vec![9, 30] }));
+    assert!(eval.contains(&Kayles { kayles: vec![9] }));
+    assert!(!eval.contains(&Kayles { kayles: vec![30] }));
+    assert_eq!(stats, eval.get_cache_stats());
+}
+
+#[test]
+fn test_warmed_cache_answers_the_query_from_the_cache() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    assert!(eval.warm((0..40).map(|n| Kayles { kayles: vec![n] })));
+    eval.reset_metrics();
+    assert_eq!(KAYLES_NIMBERS[40], eval.get_nimber(Kayles { kayles: vec![40] }));
+    assert_eq!(1, eval.metrics().cache_misses);
+    eval.stop();
+    assert!(!eval.warm((0..60).map(|n| Kayles { kayles: vec![n] })));
+}
+
+#[test]
+fn test_nimbers_are_the_mex_of_the_reachable_nimbers() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    for n in 0..25 {
+        let row = Kayles { kayles: vec![n, 3] };
+        let reachable = eval.reachable_nimbers(row.clone()).unwrap();
+        assert!(reachable.windows(2).all(|pair| pair[0] < pair[1]));
+        let mex = (0..).find(|nimber| !reachable.contains(nimber)).unwrap();
+        assert_eq!(mex, eval.get_nimber(row));
+    }
+    let mut nim: Evaluator<nim::Nim> = Evaluator::new();
+    assert_eq!(Some(vec![0, 1, 2, 3, 4]), nim.reachable_nimbers(nim::Nim::new(vec![5])));
+}
+
+/// the subtraction game {1} where every heap can also move to itself
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+struct SelfLoop(usize);
+
+impl Impartial<SelfLoop> for SelfLoop {
+    fn get_parts(self) -> Vec<SelfLoop> {
+        vec![self]
+    }
+
+    fn get_max_nimber(&self) -> usize {
+        self.0
+    }
+
+    fn get_unique_moves(&self) -> Vec<SelfLoop> {
+        let mut moves = vec![self.clone()];
+        if self.0 > 0 {
+            moves.push(SelfLoop(self.0 - 1));
+        }
+        moves
+    }
+}
+
+#[test]
+fn test_moves_to_the_position_itself_are_ignored() {
+    let mut eval: Evaluator<SelfLoop> = Evaluator::new();
+    for heap in 0..20 {
+        assert_eq!(heap % 2, eval.get_nimber(SelfLoop(heap)));
+    }
+}
+
+/// a row of heaps where a single heap may shrink by one or add a heap one larger next to itself,
+/// the latter moves loop back to the heap and are ignored like a move to the heap itself
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+struct GrowingRow(Vec<usize>);
+
+impl Impartial<GrowingRow> for GrowingRow {
+    fn get_parts(self) -> Vec<GrowingRow> {
+        self.0.into_iter().map(|heap| GrowingRow(vec![heap])).collect()
+    }
+
+    fn get_max_nimber(&self) -> usize {
+        self.0.iter().sum()
+    }
+
+    fn get_unique_moves(&self) -> Vec<GrowingRow> {
+        match self.0[..] {
+            [heap] if heap > 0 => vec![GrowingRow(vec![heap - 1]), GrowingRow(vec![heap, heap + 1])],
+            _ => vec![],
+        }
+    }
+}
+
+#[test]
+fn test_moves_to_sums_containing_the_position_are_ignored() {
+    let mut eval: Evaluator<GrowingRow> = Evaluator::new();
+    for heap in 0..20 {
+        assert_eq!(heap % 2, eval.get_nimber(GrowingRow(vec![heap])));
+    }
+    assert_eq!(1, eval.get_nimber(GrowingRow(vec![3, 4])));
+    //the larger heaps next to the position got an entry, but only the smaller heaps got evaluated
+    assert!(eval.contains(&GrowingRow(vec![20])));
+    assert!(!eval.is_known(&GrowingRow(vec![20])));
+}
+
+#[test]
+fn test_nimbers_above_the_ceiling_fail_the_evaluation() {
+    let mut eval: Evaluator<Kayles> = Evaluator::with_nimber_ceiling(3);
+    assert_eq!(Ok(3), eval.try_get_nimber(Kayles { kayles: vec![3] }));
+    //the row of 5 pins has the nimber 4
+    assert_eq!(Err(EvalError::CeilingExceeded), eval.try_get_nimber(Kayles { kayles: vec![20] }));
+    assert_eq!(Err(EvalError::CeilingExceeded), eval.try_get_nimber(Kayles { kayles: vec![2] }));
+    eval.clear_cache();
+    assert_eq!(Ok(2), eval.try_get_nimber(Kayles { kayles: vec![2] }));
+}
+
+/// kayles on a board of standing and fallen pins which is never split into rows,
+/// optionally mirror images are merged by their canonical form
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+struct PinBoard {
+    pins: Vec<bool>,
+    merge_mirrors: bool,
+}
+
+impl Impartial<PinBoard> for PinBoard {
+    fn get_parts(self) -> Vec<PinBoard> {
+        vec![self]
+    }
+
+    fn get_max_nimber(&self) -> usize {
+        self.pins.iter().filter(|pin| **pin).count()
+    }
+
+    fn get_unique_moves(&self) -> Vec<PinBoard> {
+        let mut moves = vec![];
+        for i in 0..self.pins.len() {
+            for width in 1..=2 {
+                if i + width <= self.pins.len() && self.pins[i..i + width].iter().all(|pin| *pin) {
+                    let mut pins = self.pins.clone();
+                    pins[i..i + width].fill(false);
+                    moves.push(PinBoard { pins, merge_mirrors: self.merge_mirrors });
+                }
+            }
+        }
+        moves.sort_by(|a, b| a.pins.cmp(&b.pins));
+        moves.dedup();
+        moves
+    }
+
+    fn canonical(mut self) -> Self {
+        let mirrored: Vec<bool> = self.pins.iter().rev().copied().collect();
+        if self.merge_mirrors && mirrored < self.pins {
+            self.pins = mirrored;
+        }
+        self
+    }
+}
+
+#[test]
+fn test_canonical_forms_share_cache_entries() {
+    let mut plain: Evaluator<PinBoard> = Evaluator::new();
+    let mut merged: Evaluator<PinBoard> = Evaluator::new();
+    for (n, nimber) in KAYLES_NIMBERS.iter().enumerate().take(12) {
+        let board = |merge_mirrors| PinBoard { pins: vec![true; n], merge_mirrors };
+        assert_eq!(*nimber, plain.get_nimber(board(false)));
+        assert_eq!(*nimber, merged.get_nimber(board(true)));
+    }
+    let entries = |stats: CacheStats| stats.stubs + stats.processing + stats.done;
+    assert!(entries(merged.get_cache_stats()) < entries(plain.get_cache_stats()));
+    //a mirror image is found under the canonical form
+    let mut pins = vec![true; 11];
+    pins[0] = false;
+    let mirrored: Vec<bool> = pins.iter().rev().copied().collect();
+    assert!(merged.is_known(&PinBoard { pins, merge_mirrors: true }));
+    assert!(merged.is_known(&PinBoard { pins: mirrored, merge_mirrors: true }));
+}
+
+#[test]
+fn test_merged_evaluators_know_the_nimbers_of_both() {
+    let mut low: Evaluator<Kayles> = Evaluator::new();
+    let mut high: Evaluator<Kayles> = Evaluator::new();
+    low.get_nimber(Kayles { kayles: vec![20] });
+    high.get_nimber(Kayles { kayles: vec![30] });
+    low.merge(&high);
+    assert_eq!(Some(KAYLES_NIMBERS[20]), low.get_cached_nimber(&Kayles { kayles: vec![20] }));
+    assert_eq!(Some(KAYLES_NIMBERS[30]), low.get_cached_nimber(&Kayles { kayles: vec![30] }));
+    assert!(low.get_cache_stats().done >= high.get_cache_stats().done);
+}
+
+#[test]
+#[should_panic(expected = "conflicting nimbers")]
+fn test_merging_conflicting_nimbers_panics() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    eval.get_nimber(Kayles { kayles: vec![5] });
+    let mut wrong: Evaluator<Kayles> = Evaluator::new();
+    let index = wrong.add_game(Kayles { kayles: vec![5] });
+    wrong.data[index].set_nimber(0);
+    eval.merge(&wrong);
+}
+
+#[test]
+fn test_nim_remoteness() {
+    let mut eval: Evaluator<nim::Nim> = Evaluator::new();
+    for (heaps, remoteness) in [(vec![], 0), (vec![5], 1), (vec![1, 1], 2), (vec![1, 2], 3), (vec![2, 2], 4)] {
+        assert_eq!(Some(remoteness), eval.get_remoteness(nim::Nim::new(heaps)));
+    }
+    //the p positions are exactly the positions with an even remoteness
+    for a in 0..5 {
+        for b in 0..5 {
+            let remoteness = eval.get_remoteness(nim::Nim::new(vec![a, b, 3])).unwrap();
+            assert_eq!(a ^ b ^ 3 == 0, remoteness.is_multiple_of(2));
+        }
+    }
+}
+
+#[test]
+fn test_every_done_entry_is_reported_once() {
+    let reported: Arc<Mutex<Vec<(Kayles, usize)>>> = Arc::default();
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    let sink = reported.clone();
+    eval.set_on_done(Arc::new(move |game, nimber| sink.lock().unwrap().push((game.clone(), nimber))));
+    eval.get_nimber(Kayles { kayles: vec![25, 7] });
+    let reported = reported.lock().unwrap();
+    assert_eq!(eval.get_cache_stats().done, reported.len());
+    for (i, (game, nimber)) in reported.iter().enumerate() {
+        assert_eq!(KAYLES_NIMBERS[game.kayles[0]], *nimber);
+        assert!(reported[..i].iter().all(|(other, _)| other != game));
+    }
+}
+
+#[test]
+fn test_cache_as_dot_graph() {
+    let mut eval: Evaluator<OctalGame> = Evaluator::new();
+    eval.get_nimber(octal::named("kayles").unwrap().with_heaps(vec![3]));
+    let dot = eval.to_dot();
+    assert!(dot.starts_with("digraph positions {"));
+    assert!(dot.contains("[label=\"[3]: 3\"]"));
+    assert!(dot.contains("[label=\"[1]: 1\"]"));
+    let nodes = dot.lines().filter(|line| line.contains("[label=")).count();
+    let stats = eval.get_cache_stats();
+    assert_eq!(stats.stubs + stats.processing + stats.done, nodes);
+    //the row of 3 pins can move to rows of 2 and 1 pins
+    assert!(dot.lines().filter(|line| line.contains("->")).count() >= 2);
+}
+
+#[test]
+fn test_positions_without_moves_differ_from_moves_to_the_empty_sum() {
+    let mut eval: Evaluator<Counted> = Evaluator::new();
+    assert_eq!(0, eval.get_nimber(Counted::new(0, vec![1, 2, 4, 5], Arc::new(Counters::default()))));
+    //the position without moves never got processed
+    assert_eq!(0, eval.peak_processing_entries());
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    assert_eq!(0, eval.get_nimber(Kayles { kayles: vec![] }));
+    //the only move of a single pin leads to the empty sum, which has no parts
+    assert_eq!(1, eval.get_nimber(Kayles { kayles: vec![1] }));
+    assert_eq!(1, eval.peak_processing_entries());
+}
+
+#[test]
+fn test_game_sums_have_the_xor_of_their_components() {
+    let mut eval: Evaluator<GameSum<Kayles>> = Evaluator::new();
+    let row = |n| Kayles { kayles: vec![n] };
+    let sum = GameSum(vec![row(5), row(7), row(9)]);
+    assert_eq!(4 ^ 2 ^ 4, eval.get_nimber(sum.clone()));
+    //every move changes a single component, so it also has the xor of its components
+    let mut rows: Evaluator<Kayles> = Evaluator::new();
+    for _move in sum.get_unique_moves() {
+        let nimber = _move.0.iter().fold(0, |nimber, component| nimber ^ rows.get_nimber(component.clone()));
+        assert_eq!(nimber, eval.get_nimber(_move));
+    }
+    let mut eval: Evaluator<GameSum<nim::Nim>> = Evaluator::new();
+    let nim_heaps = GameSum(vec![nim::Nim::new(vec![3]), nim::Nim::new(vec![5])]);
+    assert_eq!(Some(6), nim_heaps.as_nim_heap());
+    assert_eq!(6, eval.get_nimber(nim_heaps));
+}
+
+#[test]
+fn test_nimbers_since_a_generation_are_only_the_new_ones() {
+    let mut eval: Evaluator<Kayles> = Evaluator::new();
+    eval.get_nimber(Kayles { kayles: vec![10] });
+    let (first, generation) = eval.nimbers_since(0);
+    assert_eq!(eval.get_cache_stats().done, first.len());
+    assert!(first.contains(&(Kayles { kayles: vec![10] }, KAYLES_NIMBERS[10])));
+    assert_eq!((vec![], generation), eval.nimbers_since(generation));
+
+    eval.get_nimber(Kayles { kayles: vec![12] });
+    let (second, next_generation) = eval.nimbers_since(generation);
+    assert_eq!(first.len() + second.len(), next_generation as usize);
+    assert!(second.contains(&(Kayles { kayles: vec![12] }, KAYLES_NIMBERS[12])));
+    assert!(second.iter().all(|nimber| !first.contains(nimber)));
+}
+
+/// counts the work done on the heaps of a `Counted` game
+#[derive(Debug, Default)]
+struct Counters {
+    move_generations: AtomicUsize,
+    clones: AtomicUsize,
+    /// the heaps whose moves got generated, once for every generation
+    expanded_heaps: Mutex<Vec<usize>>,
+}
+
+impl Counters {
+    fn move_generations(&self) -> usize {
+        self.move_generations.load(Ordering::Relaxed)
+    }
+
+    fn expanded_heaps(&self) -> Vec<usize> {
+        let mut heaps = self.expanded_heaps.lock().unwrap().clone();
+        heaps.sort_unstable();
+        heaps
+    }
+
+    fn clones(&self) -> usize {
+        self.clones.load(Ordering::Relaxed)
+    }
+}
+
+/// a subtraction game counting how often the moves of its heaps get generated and how often its heaps get cloned
+/// all heaps of one game share the counters like the subtraction set, so they are left out of comparisons,
+/// sharing them through an `Rc` instead of an `Arc` makes the game neither `Send` nor `Sync`
+#[derive(Debug)]
+struct Counted<C = Arc<Counters>> {
+    game: SubtractionGame,
+    counters: C,
+    /// the max nimber every heap claims instead of the number of amounts,
+    /// `usize::MAX` leaves the bound to the move count and a cap below the real nimbers is a wrong bound
+    max_nimber: Option<usize>,
+    /// whether the heaps generate their moves lazily, the iterators hold on to the counters
+    lazy: bool,
+}
+
+impl<C: Deref<Target = Counters> + Clone> Counted<C> {
+    fn new(heap: usize, allowed: Vec<usize>, counters: C) -> Counted<C> {
+        Counted {
+            game: SubtractionGame::new(heap, allowed),
+            counters,
+            max_nimber: None,
+            lazy: false,
+        }
+    }
+
+    fn with_max_nimber(mut self, max_nimber: usize) -> Counted<C> {
+        self.max_nimber = Some(max_nimber);
+        self
+    }
+
+    fn lazy(mut self) -> Counted<C> {
+        self.lazy = true;
+        self
+    }
+
+    /// a heap of another size of the same game, without counting a clone
+    fn with_heap(&self, heap: usize) -> Counted<C> {
+        Counted {
+            game: self.game.with_heap(heap),
+            counters: self.counters.clone(),
+            max_nimber: self.max_nimber,
+            lazy: self.lazy,
+        }
+    }
+}
+
+impl<C: Deref<Target = Counters> + Clone> Clone for Counted<C> {
+    fn clone(&self) -> Self {
+        self.counters.clones.fetch_add(1, Ordering::Relaxed);
+        self.with_heap(self.game.heap)
+    }
+}
+
+impl<C> PartialEq for Counted<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.game == other.game
+    }
+}
+
+impl<C> Eq for Counted<C> {}
+
+impl<C> Hash for Counted<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.game.hash(state);
+    }
+}
+
+impl<C: Deref<Target = Counters> + Clone + 'static> Impartial<Counted<C>> for Counted<C> {
+    fn get_parts(self) -> Vec<Counted<C>> {
+        vec![self]
+    }
+
+    fn get_max_nimber(&self) -> usize {
+        match self.max_nimber {
+            Some(max_nimber) => self.game.heap.min(max_nimber),
+            None => self.game.get_max_nimber(),
+        }
+    }
+
+    fn move_count(&self) -> Option<usize> {
+        Some(self.game.allowed.iter().filter(|amount| **amount <= self.game.heap).count())
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.game.allowed.iter().all(|amount| *amount > self.game.heap)
+    }
+
+    fn get_unique_moves(&self) -> Vec<Counted<C>> {
+        self.counters.move_generations.fetch_add(1, Ordering::Relaxed);
+        self.counters.expanded_heaps.lock().unwrap().push(self.game.heap);
+        self.game
+            .get_unique_moves()
+            .into_iter()
+            .map(|_move| self.with_heap(_move.heap))
+            .collect()
+    }
+
+    fn unique_moves_iter(&self) -> Option<crate::MoveIter<Counted<C>>> {
+        if !self.lazy {
+            return None;
+        }
+        let game = self.with_heap(self.game.heap);
+        let moves = self.game.get_unique_moves().into_iter();
+        Some(Box::new(moves.map(move |_move| game.with_heap(_move.heap))))
+    }
+}
+
+#[test]
+fn test_games_which_are_not_send_evaluate_on_a_single_thread() {
+    let game = Counted::new(0, vec![1, 2], std::rc::Rc::new(Counters::default()));
+    let mut eval: Evaluator<Counted<std::rc::Rc<Counters>>> = Evaluator::builder().capacity(100).build();
+    for heap in 0..50 {
+        assert_eq!(heap % 3, eval.get_nimber(game.with_heap(heap)));
+    }
+    assert_eq!(Ok(1), eval.try_get_bounded_nimber(game.with_heap(100), 1));
+}
+
+#[test]
+fn test_games_which_are_not_send_generate_their_moves_lazily() {
+    let game = Counted::new(0, vec![1, 2], std::rc::Rc::new(Counters::default())).lazy();
+    let mut eval: Evaluator<Counted<std::rc::Rc<Counters>>> = Evaluator::new();
+    assert_eq!(Some(false), eval.is_p_position(game.with_heap(100)));
+    //the iterators of the first query are gone, the second one recreates them where the entries stopped
+    for heap in 0..50 {
+        assert_eq!(heap % 3, eval.get_nimber(game.with_heap(heap)));
+    }
+    assert_eq!(0, game.counters.move_generations());
+}
+
+const FAR: usize = 1 << 60;
+
+/// a heap with a move to every smaller heap and to two huge nim heaps, without a useful bound of the nimber
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+struct FarHeap(usize);
+
+impl Impartial<FarHeap> for FarHeap {
+    fn get_parts(self) -> Vec<FarHeap> {
+        vec![self]
+    }
+
+    fn get_max_nimber(&self) -> usize {
+        usize::MAX
+    }
+
+    fn as_nim_heap(&self) -> Option<usize> {
+        (self.0 >= FAR).then_some(self.0)
+    }
+
+    fn move_count(&self) -> Option<usize> {
+        Some(self.0 + 2)
+    }
+
+    fn get_unique_moves(&self) -> Vec<FarHeap> {
+        (0..self.0).chain([FAR, usize::MAX]).map(FarHeap).collect()
+    }
+}
+
+#[test]
+fn test_huge_nimbers_of_moves_leave_the_mex_unchanged() {
+    let mut eval: Evaluator<FarHeap> = Evaluator::new();
+    for heap in 0..20 {
+        assert_eq!(heap, eval.get_nimber(FarHeap(heap)));
+    }
+    assert_eq!(Some(FAR ^ 3), eval.get_nimber_by_parts(vec![FarHeap(FAR), FarHeap(3)]));
+    //11 and 12 are dropped when the nimber is confirmed, none of the moves reaches them
+    let expected = RuledOutNimbers { ruled_out: (0..10).collect(), nimber: Some(10) };
+    assert_eq!(Some(expected), eval.ruled_out_nimbers(&FarHeap(10)));
+}
+
+/// a position of a move graph given upfront, its moves are never generated
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+struct GraphNode(usize);
+
+impl Impartial<GraphNode> for GraphNode {
+    fn get_parts(self) -> Vec<GraphNode> {
+        vec![self]
+    }
+
+    fn get_max_nimber(&self) -> usize {
+        self.0
+    }
+
+    fn get_unique_moves(&self) -> Vec<GraphNode> {
+        unreachable!("the moves are taken from the graph")
+    }
+}
+
+/// moves from `n` to `n - 1`, `n - 2` and the sum of `n - 3` with itself
+fn move_graph(n: usize) -> HashMap<GraphNode, Vec<Vec<GraphNode>>> {
+    (0..=n)
+        .map(|node| {
+            let moves = [1, 2, 3]
+                .iter()
+                .filter(|step| **step <= node)
+                .map(|step| vec![GraphNode(node - step); if *step == 3 { 2 } else { 1 }])
+                .collect();
+            (GraphNode(node), moves)
+        })
+        .collect()
+}
+
+#[test]
+fn test_nimbers_from_a_move_graph() {
+    let graph = move_graph(30);
+    let mut eval: Evaluator<GraphNode> = Evaluator::new();
+    assert_eq!(Ok(Some(0)), eval.get_nimber_from_graph(&GraphNode(0), &graph));
+    for node in 1..=30 {
+        assert_eq!(Ok(Some((node - 1) % 3 + 1)), eval.get_nimber_from_graph(&GraphNode(node), &graph));
+    }
+    let mut dangling = move_graph(10);
+    dangling.remove(&GraphNode(4));
+    let mut eval: Evaluator<GraphNode> = Evaluator::new();
+    assert_eq!(Err(MissingPosition(GraphNode(4))), eval.get_nimber_from_graph(&GraphNode(10), &dangling));
+    assert!(!eval.contains(&GraphNode(10)));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "a max nimber is too low")]
+fn test_nimbers_confirmed_by_a_too_low_max_nimber_are_reported() {
+    //the subtraction game {1, 2} claiming that no nimber exceeds 1, which is wrong from a heap of 2 on
+    let game = Counted::new(0, vec![1, 2], Arc::new(Counters::default())).with_max_nimber(1);
+    let mut eval: Evaluator<Counted> = Evaluator::new();
+    assert_eq!(1, eval.get_nimber(game.with_heap(1)));
+    assert!(!eval.had_bound_violation());
+    //ruling out 1 leaves 0 as the only candidate, although the move to 0 has the nimber 0
+    eval.get_nimber(game.with_heap(2));
+}
+
+#[test]
+fn test_games_reached_by_moves_are_never_cloned() {
+    let counters = Arc::new(Counters::default());
+    let mut eval: Evaluator<Counted> = Evaluator::new();
+    assert_eq!(300 % 3, eval.get_nimber(Counted::new(300, vec![1, 2], counters.clone())));
+    assert_eq!(301, eval.data.len());
+    assert_eq!(0, counters.clones());
+}
+
+#[test]
+fn test_max_nimbers_get_tightened_by_the_move_count() {
+    let tight = Counted::new(0, vec![1, 2], Arc::new(Counters::default()));
+    let loose = tight.clone().with_max_nimber(usize::MAX);
+    let mut loose_eval: Evaluator<Counted> = Evaluator::new();
+    let mut tight_eval: Evaluator<Counted> = Evaluator::new();
+    for heap in 0..50 {
+        assert_eq!(heap % 3, loose_eval.get_nimber(loose.with_heap(heap)));
+        assert_eq!(heap % 3, tight_eval.get_nimber(tight.with_heap(heap)));
+    }
+    //generating the moves of the heap alone already bounds its nimber by the number of moves
+    let mut eval: Evaluator<Counted> = Evaluator::new();
+    match eval.get_nimber_or_estimate(loose.with_heap(40), Duration::ZERO) {
+        NimberResult::Bounds { upper, .. } => assert_eq!(2, upper),
+        NimberResult::Exact(nimber) => assert_eq!(40 % 3, nimber),
+    }
+    //a move count known upfront already bounds positions which are not expanded yet
+    let index = eval.get_index_of(&loose.with_heap(60));
+    assert_eq!((0, 2), eval.data[index].get_nimber_bounds());
+}
+
+/// a fan of `size` moves to short rows which only allow taking single pins,
+/// the fan itself claims a loose bound on its nimber
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+struct Fan {
+    size: usize,
+    row: bool,
+}
+
+impl Impartial<Fan> for Fan {
+    fn get_parts(self) -> Vec<Fan> {
+        vec![self]
+    }
+
+    fn get_max_nimber(&self) -> usize {
+        if self.row {
+            self.size.min(1)
+        } else {
+            self.size
+        }
+    }
+
+    fn get_unique_moves(&self) -> Vec<Fan> {
+        if self.row {
+            (self.size > 0).then(|| Fan { size: self.size - 1, row: true }).into_iter().collect()
+        } else {
+            (0..self.size).map(|size| Fan { size, row: true }).collect()
+        }
+    }
+}
+
+#[test]
+fn test_max_nimbers_get_tightened_by_the_bounds_of_the_moves() {
+    //no move can have a nimber above 1, so the fan can't have a nimber above 2
+    let mut eval: Evaluator<Fan> = Evaluator::new();
+    match eval.get_nimber_or_estimate(Fan { size: 30, row: false }, Duration::ZERO) {
+        NimberResult::Bounds { upper, .. } => assert_eq!(2, upper),
+        NimberResult::Exact(nimber) => assert_eq!(2, nimber),
+    }
+    assert_eq!(2, eval.get_nimber(Fan { size: 30, row: false }));
+    assert_eq!(1, eval.get_nimber(Fan { size: 1, row: false }));
+    assert_eq!(0, eval.get_nimber(Fan { size: 0, row: false }));
+}
+
+static LAZY_MOVES: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// a nim heap which generates its moves to the smaller heaps lazily, starting with the empty heap
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+struct LazyHeap(usize);
+
+impl Impartial<LazyHeap> for LazyHeap {
+    fn get_parts(self) -> Vec<LazyHeap> {
+        vec![self]
+    }
+
+    fn get_max_nimber(&self) -> usize {
+        self.0
+    }
+
+    fn get_unique_moves(&self) -> Vec<LazyHeap> {
+        (0..self.0).map(LazyHeap).collect()
+    }
+
+    fn unique_moves_iter(&self) -> Option<crate::MoveIter<LazyHeap>> {
+        Some(Box::new((0..self.0).map(|heap| {
+            LAZY_MOVES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            LazyHeap(heap)
+        })))
+    }
+}
+
+#[test]
+fn test_lazy_moves_are_only_generated_when_needed() {
+    let mut eval: Evaluator<LazyHeap> = Evaluator::new();
+    //the move to the empty heap already shows the heap is no p-position
+    assert_eq!(Some(false), eval.is_p_position(LazyHeap(1_000_000)));
+    assert_eq!(1, LAZY_MOVES.load(std::sync::atomic::Ordering::Relaxed));
+    //clones recreate the iterators where the entries stopped taking moves
+    assert_eq!(Some(false), eval.is_p_position(LazyHeap(40)));
+    let mut clone = eval.clone();
+    assert_eq!(40, clone.get_nimber(LazyHeap(40)));
+    assert_eq!(40, eval.get_nimber(LazyHeap(40)));
+}
+
+#[test]
+fn test_terminal_positions_need_no_move_generation() {
+    let counters = Arc::new(Counters::default());
+    let game = Counted::new(0, vec![1, 2], counters.clone());
+    let mut eval: Evaluator<Counted> = Evaluator::new();
+    assert_eq!(0, eval.get_nimber(game.with_heap(30)));
+    assert_eq!(0, eval.get_nimber(game.with_heap(0)));
+    //every heap but the empty one generated its moves exactly once
+    assert_eq!(30, counters.move_generations());
+}
+
+#[test]
+fn test_positions_are_expanded_once_despite_interruptions() {
+    let counters = Arc::new(Counters::default());
+    let game = Counted::new(0, vec![1, 2, 3], counters.clone());
+    let mut eval: Evaluator<Counted> = Evaluator::new();
+    assert_eq!(Err(EvalError::BoundExceeded), eval.try_get_bounded_nimber(game.with_heap(403), 0));
+    assert_eq!(403 % 4, eval.get_nimber(game.with_heap(403)));
+    //the moves of an interrupted position are queued again instead of being generated again,
+    //so the moves of every heap but the empty one got generated exactly once
+    assert_eq!((1..=403).collect::<Vec<usize>>(), counters.expanded_heaps());
+}
+
+#[test]
+fn test_threads_expand_every_shared_position_once() {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(16).build().unwrap();
+    for round in 0..8 {
+        //every thread starts from heaps of its own, but reaches most of the heaps below them as well,
+        //the rounds start from a cache which already knows a few heaps
+        let counters = Arc::new(Counters::default());
+        let game = Counted::new(0, vec![1, 2, 3], counters.clone());
+        let mut eval: Evaluator<Counted> = Evaluator::new();
+        eval.get_nimber(game.with_heap(20 * round));
+        let known = counters.expanded_heaps();
+        let heaps: Vec<Counted> = (0..64).map(|i| game.with_heap(200 + (i * 37 + round) % 200)).collect();
+        let nimbers = eval.get_nimbers_in_pool(&heaps, &pool);
+        for (heap, nimber) in heaps.iter().zip(nimbers) {
+            assert_eq!(heap.game.heap % 4, nimber);
+        }
+        let largest = heaps.iter().map(|heap| heap.game.heap).max().unwrap();
+        let mut expanded = counters.expanded_heaps();
+        expanded.retain(|heap| !known.contains(heap));
+        assert_eq!((known.len() + 1..=largest).collect::<Vec<usize>>(), expanded);
+        //the merged cache knows every heap
+        let generations = counters.move_generations();
+        assert_eq!(largest % 4, eval.get_nimber(game.with_heap(largest)));
+        assert_eq!(generations, counters.move_generations());
+    }
+}
@@ -0,0 +1,52 @@
+use crate::Impartial;
+use serde::{Deserialize, Serialize};
+
+/// wythoff's game, a queen on a board that may only move towards the corner
+/// a move reduces `a`, `b` or both by the same amount,
+/// the coordinates are kept sorted since mirrored positions are equivalent,
+/// so positions are only created through `new`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Wythoff {
+    a: usize,
+    b: usize,
+}
+
+impl Wythoff {
+    pub fn new(a: usize, b: usize) -> Wythoff {
+        Wythoff {
+            a: a.min(b),
+            b: a.max(b),
+        }
+    }
+}
+
+impl Impartial<Wythoff> for Wythoff {
+    fn get_parts(self) -> Vec<Wythoff> {
+        vec![self]
+    }
+    /// the nimbers along a row or a column are all different, so the nimber can not exceed `a + b`
+    fn get_max_nimber(&self) -> usize {
+        self.a + self.b
+    }
+    fn get_unique_moves(&self) -> Vec<Wythoff> {
+        let mut moves = vec![];
+        for smaller in 0..self.a {
+            moves.push(Wythoff::new(smaller, self.b));
+            moves.push(Wythoff::new(smaller, self.b - (self.a - smaller)));
+        }
+        for smaller in 0..self.b {
+            moves.push(Wythoff::new(self.a, smaller));
+        }
+        //mirrored moves can coincide
+        moves.sort_unstable_by_key(|position| (position.a, position.b));
+        moves.dedup();
+        moves
+    }
+    /// with one coordinate at 0 only a single nim heap is left
+    fn as_nim_heap(&self) -> Option<usize> {
+        (self.a == 0).then_some(self.b)
+    }
+    fn label(&self) -> String {
+        format!("({}, {})", self.a, self.b)
+    }
+}